@@ -0,0 +1,46 @@
+// Throughput benchmarks for the Intcode VM:
+//   cargo bench --bench intcode
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use util::intcode;
+use util::intcode::Word;
+
+fn tape(input: &str) -> Vec<Word> {
+    util::input_to_vec_t_fromstr(input, ',')
+}
+
+// Day 2 part 2: run the program once for every noun/verb pair
+fn day2_search(c: &mut Criterion) {
+    let tape = tape(include_str!("../src/bin/day2/input"));
+    c.bench_function("day2 noun/verb search", |b| b.iter(|| {
+        let mut found = None;
+        for noun in 0..100 {
+            for verb in 0..100 {
+                let memory = intcode::run_patched(&tape, &[(1, noun), (2, verb)]).unwrap();
+                if memory.get(0) == black_box(19690720) {
+                    found = Some(100 * noun + verb);
+                }
+            }
+        }
+        found
+    }));
+}
+
+// Day 5 part 2: the thermal radiator diagnostic
+fn day5_diagnostic(c: &mut Criterion) {
+    let tape = tape(include_str!("../src/bin/day5/input"));
+    c.bench_function("day5 diagnostic", |b| b.iter(|| {
+        intcode::run_program(black_box(&tape), &[5]).unwrap()
+    }));
+}
+
+// A long-running loop, counting down from a million in steps of one
+fn countdown(c: &mut Criterion) {
+    let tape = [1101,1000000,0,14,1001,14,-1,14,1005,14,4,104,0,99,0];
+    c.bench_function("countdown from 1000000", |b| b.iter(|| {
+        intcode::run_program(black_box(&tape), &[]).unwrap()
+    }));
+}
+
+criterion_group!(benches, day2_search, day5_diagnostic, countdown);
+criterion_main!(benches);
@@ -27,19 +27,19 @@ fn get_total_fuel_requirements(mass: i32) -> i32 {
     let mut fuel = get_initial_fuel_requirements(mass);
 
     while fuel > 0 {
-        total = total + fuel;
+        total += fuel;
         fuel = get_initial_fuel_requirements(fuel);
     }
     total
 }
 
-fn part1(input: &Vec<i32>) -> i32 {
-    let res: i32 = input.into_iter().map(|x| get_initial_fuel_requirements(*x)).sum();
+fn part1(input: &[i32]) -> i32 {
+    let res: i32 = input.iter().map(|x| get_initial_fuel_requirements(*x)).sum();
     res
 }
 
-fn part2(input: &Vec<i32>) -> i32 {
-    let res: i32 = input.into_iter().map(|x| get_total_fuel_requirements(*x)).sum();
+fn part2(input: &[i32]) -> i32 {
+    let res: i32 = input.iter().map(|x| get_total_fuel_requirements(*x)).sum();
     res
 }
 
@@ -74,11 +74,11 @@ fn has_any_entry_repeated_exactly_once(digits: Vec<u32>) -> bool{
 }
 
 fn part1(range: Range<i32>) -> usize {
-    range.filter(|x| is_valid_password_part1(x)).count()
+    range.filter(is_valid_password_part1).count()
 }
 
 fn part2(range: Range<i32>) -> usize {
-    range.filter(|x| is_valid_password_part2(x)).count()
+    range.filter(is_valid_password_part2).count()
 }
 
 fn main(){
@@ -1,17 +1,16 @@
-use util::intcode::intcode;
+use util::intcode;
+use util::intcode::Word;
 
-fn part1(input: &Vec<i32>) -> i32 {
-    let mut program = input.clone();
-    intcode::run_program(&mut program, Some(1))
+fn part1(input: &[Word]) -> Word {
+    intcode::check_diagnostics(&intcode::run_program(input, &[1]).unwrap())
 }
 
-fn part2(input: &Vec<i32>) -> i32 {
-    let mut program = input.clone();
-    intcode::run_program(&mut program, Some(5))
+fn part2(input: &[Word]) -> Word {
+    intcode::check_diagnostics(&intcode::run_program(input, &[5]).unwrap())
 }
 
 fn main(){
-    let input: Vec<i32> = util::input_to_vec_t_fromstr(include_str!("input"),',');
+    let input: Vec<Word> = util::input_to_vec_t_fromstr(include_str!("input"),',');
     println!("Part 1: Answer is {}", part1(&input));
     println!("Part 2: Answer is {}", part2(&input));
 }
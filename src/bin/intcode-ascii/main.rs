@@ -0,0 +1,67 @@
+// Runs an ASCII Intcode program against the terminal, so text-driven puzzles
+// (e.g. day 25's adventure game) can be played by hand:
+//   cargo run --bin intcode-ascii <tape> [--record <session> | --replay <session>]
+// Each line typed is sent as input followed by a newline. Output outside the
+// ASCII range is printed as a number on a line of its own. With --record,
+// everything that goes in and out is written to the session file as it
+// happens, so a session cut short by Ctrl-C can still be replayed; with
+// --replay, a saved session is run again without the terminal and checked
+// against what was recorded
+use std::env;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::process;
+
+use util::intcode::device::Stdio;
+use util::intcode::replay::{self, Recorder, Session};
+use util::intcode::{Machine, Status, Word};
+
+const USAGE: &str = "Usage: intcode-ascii <tape> [--record <session> | --replay <session>]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let path = args.first().expect(USAGE);
+    let input = fs::read_to_string(path).unwrap();
+    let tape: Vec<Word> = util::input_to_vec_t_fromstr(&input, ',');
+    let mut machine = Machine::new(&tape);
+
+    match (args.get(1).map(|x| x.as_str()), args.get(2)) {
+        (None, _) => {
+            report(machine.run_with(&mut Stdio::ascii()));
+        },
+        (Some("--record"), Some(file)) => {
+            let mut recorder = match File::create(file).and_then(|x| Recorder::new(BufWriter::new(x))) {
+                Ok(x)  => x,
+                Err(e) => fail(&format!("Can't record to {}: {}", file, e)),
+            };
+            report(machine.run_with_traced(&mut Stdio::ascii(), &mut recorder));
+            if let Err(e) = recorder.finish() {
+                fail(&format!("Recording to {} failed: {}", file, e));
+            }
+        },
+        (Some("--replay"), Some(file)) => {
+            let session = match Session::load(file) {
+                Ok(x)  => x,
+                Err(e) => fail(&format!("Can't replay {}: {}", file, e)),
+            };
+            match replay::replay(&mut machine, &session) {
+                Ok(status) => println!("Matched the recording, finishing with {:?}", status),
+                Err(e)     => println!("Diverged at {}", e),
+            }
+        },
+        _ => panic!("{}", USAGE),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1)
+}
+
+fn report(result: Result<Status, util::intcode::IntcodeError>) {
+    match result {
+        Ok(Status::Halted) => {},
+        Ok(_)              => eprintln!("\nEnd of input, but the program wanted more"),
+        Err(e)             => eprintln!("\nError: {}", e),
+    }
+}
@@ -0,0 +1,31 @@
+// Prints the control flow graph of an Intcode tape in Graphviz DOT format,
+// e.g.
+//   cargo run --bin intcode-cfg src/bin/day5/input 6=1105 | dot -Tsvg > day5.svg
+// Values can be patched into the tape first with address=value arguments.
+// A summary of which cells are code, data or self-modified goes to stderr
+use std::env;
+use std::fs;
+use std::process;
+
+use util::intcode;
+use util::intcode::analysis::{analyse, Cell};
+use util::intcode::Word;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let path = args.get(1).expect("Usage: intcode-cfg <tape> [address=value]...");
+    let input = fs::read_to_string(path).unwrap();
+    let mut tape: Vec<Word> = util::input_to_vec_t_fromstr(&input, ',');
+    if let Err(e) = intcode::patch_tape(&mut tape, &args[2..]) {
+        eprintln!("{}", e);
+        process::exit(1)
+    }
+
+    let analysis = analyse(&tape);
+    print!("{}", analysis.to_dot(&tape));
+    eprintln!("{} blocks, {} computed jumps", analysis.blocks.len(), analysis.computed_jumps().len());
+    for kind in [Cell::Code, Cell::Data, Cell::SelfModified, Cell::Unused].iter() {
+        eprintln!("{:?}: {} cells", kind, analysis.cells_of(*kind).len());
+    }
+    eprintln!("Self-modified: {:?}", analysis.cells_of(Cell::SelfModified));
+}
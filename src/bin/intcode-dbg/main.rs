@@ -0,0 +1,207 @@
+// An interactive debugger for Intcode programs, e.g.
+//   cargo run --bin intcode-dbg src/bin/day5/input
+// Type 'help' at the prompt for a list of commands
+use std::env;
+use std::fs;
+use std::io;
+use std::io::{BufRead, Write};
+
+use util::intcode::debugger::{Debugger, Stop};
+use util::intcode::disasm;
+use util::intcode::trace::TraceEvent;
+use util::intcode::{Machine, Opcode, Word};
+
+const HELP: &str = "\
+Commands:
+  s, step [n]          execute n instructions (default 1), showing each one
+  c, continue [n]      run until a breakpoint, watchpoint, halt or input is
+                       needed, or for at most n instructions
+  b, break <addr>      break before executing the instruction at addr
+  b, break <MNEMONIC>  break before executing any instruction with this opcode
+  w, watch <addr>      stop whenever the value at addr changes
+  clear                remove all breakpoints and watchpoints
+  info                 list breakpoints and watchpoints
+  r, regs              show the instruction pointer, relative base and I/O
+  x <addr> [n]         dump n values of memory from addr (default 8)
+  l, list [addr] [n]   disassemble n instructions from addr (default: ip, 8)
+  i, input <v>...      queue values as input
+  q, quit              exit";
+
+fn show_event(event: &TraceEvent) {
+    println!("  {}", event);
+}
+
+fn show_stop(debugger: &Debugger, stop: Stop) {
+    match stop {
+        Stop::Breakpoint(x)       => println!("Breakpoint at {:04}", x),
+        Stop::OpcodeBreakpoint(x) => {
+            println!("Breakpoint on {} at {:04}", x.mnemonic(), debugger.machine.ip())
+        },
+        Stop::Watchpoint { address, old, new } => {
+            println!("Watchpoint: [{}] changed from {} to {}", address, old, new)
+        },
+        Stop::Steps           => {},
+        Stop::Halted          => println!("Program halted"),
+        Stop::WaitingForInput => println!("Waiting for input (use 'input')"),
+    }
+}
+
+fn show_registers(machine: &Machine) {
+    println!("ip:            {:04}", machine.ip());
+    println!("relative base: {}", machine.relative_base());
+    println!("status:        {:?}", machine.status());
+    println!("pending input: {:?}", machine.pending_input());
+    println!("output:        {:?}", machine.output());
+}
+
+// Most values or instructions x or list will show at once, so a mistyped
+// count doesn't try to fill the terminal (or memory)
+const MAX_COUNT: usize = 4096;
+
+// The addresses from address up to count values on
+fn span(address: usize, count: usize) -> Result<std::ops::Range<usize>, String> {
+    match address.checked_add(count) {
+        Some(end) => Ok(address..end),
+        None      => Err(format!("Address {} is too large", address)),
+    }
+}
+
+fn check_count(count: usize) -> Result<(), String> {
+    match count > MAX_COUNT {
+        true  => Err(format!("Can only show {} at a time", MAX_COUNT)),
+        false => Ok(()),
+    }
+}
+
+fn dump_memory(machine: &Machine, address: usize, count: usize) -> Result<(), String> {
+    check_count(count)?;
+    let span = span(address, count)?;
+    for row in span.clone().step_by(8) {
+        let end = std::cmp::min(row + 8, span.end);
+        let values: Vec<String> = (row..end)
+            .map(|x| format!("{:>8}", machine.memory().get(x)))
+            .collect();
+        println!("{:04}: {}", row, values.join(" "));
+    }
+    Ok(())
+}
+
+fn list(machine: &Machine, address: usize, count: usize) -> Result<(), String> {
+    // Decode straight from memory, so that any changes the program has made
+    // to itself show up. Instructions are at most 4 values long
+    check_count(count)?;
+    let memory: Vec<Word> = span(address, count * 4)?.map(|x| machine.memory().get(x)).collect();
+    let mut offset = 0;
+    for _ in 0..count {
+        let line = match disasm::decode(&memory, offset) {
+            Some(disasm::Line::Instruction { opcode, operands, .. }) => {
+                disasm::Line::Instruction { address: address + offset, opcode, operands }
+            },
+            _ => disasm::Line::Data { address: address + offset, value: memory[offset] },
+        };
+        let marker = if line.address() == machine.ip() { "=>" } else { "  " };
+        println!("{} {}", marker, line);
+        offset += line.size();
+    }
+    Ok(())
+}
+
+fn parse<T: std::str::FromStr>(arg: Option<&&str>, default: T) -> Result<T, String> {
+    match arg {
+        Some(x) => x.parse::<T>().map_err(|_| format!("Invalid number {:?}", x)),
+        None    => Ok(default),
+    }
+}
+
+// Runs a single command. Returns false when it's time to quit
+fn run_command(debugger: &mut Debugger, line: &str) -> Result<bool, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let command = match words.first() {
+        Some(x) => *x,
+        None    => { return Ok(true) },
+    };
+    let args = &words[1..];
+
+    match command {
+        "s" | "step" => {
+            let count: usize = parse(args.first(), 1)?;
+            let stop = debugger.resume(Some(count), &mut show_event)
+                .map_err(|e| e.to_string())?;
+            show_stop(debugger, stop);
+        },
+        "c" | "continue" => {
+            let limit = match args.first() {
+                Some(_) => Some(parse(args.first(), 0)?),
+                None    => None,
+            };
+            let stop = debugger.resume(limit, &mut |event| {
+                if let Some(x) = event.output {
+                    println!("Output: {}", x);
+                }
+            }).map_err(|e| e.to_string())?;
+            show_stop(debugger, stop);
+        },
+        "b" | "break" => {
+            let target = args.first().ok_or("Usage: break <addr|MNEMONIC>")?;
+            match Opcode::from_mnemonic(target) {
+                Some(x) => debugger.add_opcode_breakpoint(x),
+                None    => debugger.add_breakpoint(parse(Some(target), 0)?),
+            }
+        },
+        "w" | "watch" => {
+            let address = args.first().ok_or("Usage: watch <addr>")?;
+            debugger.add_watchpoint(parse(Some(address), 0)?);
+        },
+        "clear" => debugger.clear(),
+        "info" => {
+            println!("Breakpoints: {:?}", debugger.breakpoints());
+            let opcodes: Vec<&str> = debugger.opcode_breakpoints().iter()
+                .map(|x| x.mnemonic())
+                .collect();
+            println!("Opcode breakpoints: {:?}", opcodes);
+            println!("Watchpoints: {:?}", debugger.watchpoints().keys().collect::<Vec<&usize>>());
+        },
+        "r" | "regs" => show_registers(&debugger.machine),
+        "x" => {
+            let address = args.first().ok_or("Usage: x <addr> [n]")?;
+            dump_memory(&debugger.machine, parse(Some(address), 0)?, parse(args.get(1), 8)?)?;
+        },
+        "l" | "list" => {
+            let address = parse(args.first(), debugger.machine.ip())?;
+            list(&debugger.machine, address, parse(args.get(1), 8)?)?;
+        },
+        "i" | "input" => {
+            for arg in args {
+                let value: Word = parse(Some(arg), 0)?;
+                debugger.machine.push_input(value);
+            }
+        },
+        "h" | "help" => println!("{}", HELP),
+        "q" | "quit" => { return Ok(false) },
+        _ => { return Err(format!("Unknown command {:?}, try 'help'", command)) },
+    }
+    Ok(true)
+}
+
+fn main() {
+    let path = env::args().nth(1).expect("Usage: intcode-dbg <tape>");
+    let input = fs::read_to_string(&path).unwrap();
+    let tape: Vec<Word> = util::input_to_vec_t_fromstr(&input, ',');
+    let mut debugger = Debugger::new(Machine::new(&tape));
+
+    let stdin = io::stdin();
+    loop {
+        print!("({:04}) ", debugger.machine.ip());
+        io::stdout().flush().unwrap();
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap() == 0 {
+            break
+        }
+        match run_command(&mut debugger, &line) {
+            Ok(true)  => {},
+            Ok(false) => break,
+            Err(e)    => println!("{}", e),
+        }
+    }
+}
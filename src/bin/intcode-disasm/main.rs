@@ -0,0 +1,14 @@
+// Prints a disassembly listing of an Intcode tape, e.g.
+//   cargo run --bin intcode-disasm src/bin/day5/input
+use std::env;
+use std::fs;
+
+use util::intcode::disasm;
+use util::intcode::Word;
+
+fn main() {
+    let path = env::args().nth(1).expect("Usage: intcode-disasm <tape>");
+    let input = fs::read_to_string(&path).unwrap();
+    let tape: Vec<Word> = util::input_to_vec_t_fromstr(&input, ',');
+    println!("{}", disasm::listing(&tape));
+}
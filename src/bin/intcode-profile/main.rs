@@ -0,0 +1,30 @@
+// Runs an Intcode tape and reports where it spent its time, e.g.
+//   cargo run --release --bin intcode-profile src/bin/day5/input 5
+// Any arguments after the tape are given to the program as input. With
+// --json, the whole profile is printed as JSON instead of a report
+use std::env;
+use std::fs;
+
+use util::intcode::profile::Profile;
+use util::intcode::{Machine, Word};
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let json = args.iter().any(|x| x == "--json");
+    args.retain(|x| x != "--json");
+    let path = args.first().expect("Usage: intcode-profile [--json] <tape> [input]...");
+    let input = fs::read_to_string(path).unwrap();
+    let tape: Vec<Word> = util::input_to_vec_t_fromstr(&input, ',');
+
+    let mut machine = Machine::new(&tape);
+    machine.extend_input(args[1..].iter().map(|x| x.parse::<Word>().unwrap()));
+    let mut profile = Profile::new();
+    let status = machine.run_traced(&mut profile);
+
+    if json {
+        println!("{}", profile.to_json());
+        return
+    }
+    println!("Finished with {:?}, output {:?}\n", status, machine.output());
+    print!("{}", profile.report(10, tape.len()));
+}
@@ -0,0 +1,25 @@
+// Translates an Intcode tape into a Rust module which runs it natively, e.g.
+//   cargo run --bin intcode-transpile src/bin/day5/input > day5.rs
+// Values can be patched into the tape first with address=value arguments,
+// e.g. day 2's noun and verb:
+//   cargo run --bin intcode-transpile src/bin/day2/input 1=12 2=2
+// The module's run(&mut Machine) can be used in place of Machine::run()
+use std::env;
+use std::fs;
+use std::process;
+
+use util::intcode;
+use util::intcode::transpile;
+use util::intcode::Word;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let path = args.get(1).expect("Usage: intcode-transpile <tape> [address=value]...");
+    let input = fs::read_to_string(path).unwrap();
+    let mut tape: Vec<Word> = util::input_to_vec_t_fromstr(&input, ',');
+    if let Err(e) = intcode::patch_tape(&mut tape, &args[2..]) {
+        eprintln!("{}", e);
+        process::exit(1)
+    }
+    print!("{}", transpile::transpile(&tape));
+}
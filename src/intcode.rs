@@ -1,190 +1,923 @@
-pub mod intcode {
-    #[derive(PartialEq)]
-    #[derive(Debug)]
-    pub enum Opcode {
-        Stop,
-        Add,
-        Mul,
-        Mov,
-        Out,
-        Jnz,
-        Jz,
-        Lt,
-        Eq,
-    }
-
-    #[derive(PartialEq)]
-    #[derive(Debug)]
-    pub enum ParameterMode {
-        Position,
-        Immediate,
-    }
-
-    #[test]
-    fn test_get_opcode() {
-        assert_eq!(get_opcode(&12304), Opcode::Out);
-        assert_eq!(get_opcode(&12301), Opcode::Add);
-        assert_eq!(get_opcode(&12399), Opcode::Stop);
-    }
-    fn get_opcode(raw_value: &i32) -> Opcode {
-        match raw_value % 100 {
-            99 => Opcode::Stop,
-            1  => Opcode::Add,
-            2  => Opcode::Mul,
-            3  => Opcode::Mov,
-            4  => Opcode::Out,
-            5  => Opcode::Jnz,
-            6  => Opcode::Jz,
-            7  => Opcode::Lt,
-            8  => Opcode::Eq,
-            _  => { panic!("Invalid/unimplemented opcode {:?}", raw_value) }
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+pub mod analysis;
+pub mod ascii;
+pub mod asm;
+pub mod debugger;
+pub mod decode;
+pub mod device;
+pub mod disasm;
+pub mod instruction_set;
+mod error;
+mod memory;
+pub mod network;
+pub mod profile;
+pub mod replay;
+pub mod search;
+pub mod symbolic;
+pub mod snapshot;
+pub mod threaded;
+pub mod trace;
+pub mod transpile;
+pub use self::error::IntcodeError;
+pub use self::memory::Memory;
+use self::decode::{DecodeCache, Instruction};
+use self::device::{Device, Queue};
+use self::instruction_set::InstructionSet;
+use self::snapshot::Snapshot;
+use self::trace::TraceSink;
+
+// The size of a single value in Intcode memory.
+// Puzzles from day 9 onward expect values well beyond the range of an i32
+pub type Word = i64;
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Opcode {
+    Stop,
+    Add,
+    Mul,
+    Mov,
+    Out,
+    Jnz,
+    Jz,
+    Lt,
+    Eq,
+    AdjustRelativeBase,
+}
+
+#[test]
+fn test_opcode_table() {
+    for (i, info) in OPCODES.iter().enumerate() {
+        assert_eq!(info.opcode as usize, i);
+    }
+}
+// Everything about one of the standard opcodes
+struct OpcodeInfo {
+    opcode: Opcode,
+    value: Word,
+    mnemonic: &'static str,
+    num_args: usize,
+    // Whether the last argument is an address to write to
+    writes: bool,
+}
+
+impl OpcodeInfo {
+    // Which argument, if any, is an address to write to
+    fn dest(&self) -> Option<usize> {
+        match self.writes {
+            true  => Some(self.num_args - 1),
+            false => None,
         }
     }
+}
 
-    pub fn run_program(tape: &Vec<i32>, input: Option<i32>) -> i32 {
-        // Runs an Intcode program and returns its final state as Vec<i32>
-        let mut program = tape.clone();
+// The standard instruction set, in the same order as Opcode. Machines can be
+// given a different one with an InstructionSet
+const OPCODES: [OpcodeInfo; 10] = [
+    OpcodeInfo { opcode: Opcode::Stop, value: 99, mnemonic: "STOP", num_args: 0, writes: false },
+    OpcodeInfo { opcode: Opcode::Add,  value: 1,  mnemonic: "ADD",  num_args: 3, writes: true },
+    OpcodeInfo { opcode: Opcode::Mul,  value: 2,  mnemonic: "MUL",  num_args: 3, writes: true },
+    OpcodeInfo { opcode: Opcode::Mov,  value: 3,  mnemonic: "MOV",  num_args: 1, writes: true },
+    OpcodeInfo { opcode: Opcode::Out,  value: 4,  mnemonic: "OUT",  num_args: 1, writes: false },
+    OpcodeInfo { opcode: Opcode::Jnz,  value: 5,  mnemonic: "JNZ",  num_args: 2, writes: false },
+    OpcodeInfo { opcode: Opcode::Jz,   value: 6,  mnemonic: "JZ",   num_args: 2, writes: false },
+    OpcodeInfo { opcode: Opcode::Lt,   value: 7,  mnemonic: "LT",   num_args: 3, writes: true },
+    OpcodeInfo { opcode: Opcode::Eq,   value: 8,  mnemonic: "EQ",   num_args: 3, writes: true },
+    OpcodeInfo { opcode: Opcode::AdjustRelativeBase, value: 9, mnemonic: "ARB", num_args: 1, writes: false },
+];
 
-        let mut pos: usize = 0;
-        let mut output: i32 = 0;
-        let mut opcode = get_opcode(&program[pos]);
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum ParameterMode {
+    Position,
+    Immediate,
+    Relative,
+}
 
-        while opcode != Opcode::Stop {
-            if output != 0 {
-                panic!("TEST failed: invalid output {:?}", output)
-            }
+// The state a Machine is left in after executing an instruction.
+// Running means it can carry on stepping; anything else is a reason to
+// hand control back to whoever is driving the machine.
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Status {
+    Running,
+    Halted,
+    WaitingForInput,
+    Output(Word),
+}
 
-            match run_instruction(&opcode, &mut program, &mut pos, input) {
-                Some(x) => { output = x },
-                None    => {},
-            }
-            opcode = get_opcode(&program[pos]);
+// Guards against programs which never halt, for run_limited()
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy, Default)]
+pub struct Limits {
+    // Give up after this many instructions
+    pub max_steps: Option<usize>,
+    // Give up if the machine gets back to a state it has already been in
+    // (same ip, relative base and memory) without any input or output in
+    // between. This hashes the whole of memory on every step, so it is slow
+    // for large programs. Matching hashes are only taken as a loop once the
+    // states themselves have been compared, so this never stops a program
+    // which would have finished
+    pub detect_loops: bool,
+}
+
+#[test]
+fn test_get_opcode() {
+    assert_eq!(get_opcode(&12304), Some(Opcode::Out));
+    assert_eq!(get_opcode(&12301), Some(Opcode::Add));
+    assert_eq!(get_opcode(&12399), Some(Opcode::Stop));
+    assert_eq!(get_opcode(&209), Some(Opcode::AdjustRelativeBase));
+    assert_eq!(get_opcode(&12342), None);
+}
+fn get_opcode(raw_value: &Word) -> Option<Opcode> {
+    OPCODES.iter().find(|x| x.value == raw_value % 100).map(|x| x.opcode)
+}
+
+#[test]
+fn test_opcode_round_trip() {
+    for value in (1..10).chain(99..100) {
+        let opcode = get_opcode(&value).unwrap();
+        assert_eq!(opcode.value(), value);
+        assert_eq!(Opcode::from_mnemonic(opcode.mnemonic()), Some(opcode));
+    }
+    assert_eq!(Opcode::from_mnemonic("jnz"), Some(Opcode::Jnz));
+    assert_eq!(Opcode::from_mnemonic("NOP"), None);
+}
+impl Opcode {
+    fn info(&self) -> &'static OpcodeInfo {
+        &OPCODES[*self as usize]
+    }
+
+    // Short name used in assembly listings
+    pub fn mnemonic(&self) -> &'static str {
+        self.info().mnemonic
+    }
+
+    // Looks up an opcode by its mnemonic, ignoring case
+    pub fn from_mnemonic(mnemonic: &str) -> Option<Opcode> {
+        OPCODES.iter().find(|x| x.mnemonic.eq_ignore_ascii_case(mnemonic)).map(|x| x.opcode)
+    }
+
+    // The numeric value of the opcode, i.e. the last two digits of an
+    // instruction using it
+    pub fn value(&self) -> Word {
+        self.info().value
+    }
+}
+
+// An Intcode computer which owns its memory and instruction pointer, so it
+// can be paused (e.g. to wait for input, or to hand over an output) and
+// resumed later from exactly where it left off
+//
+// By default, input is queued up ahead of time and consumed one value per
+// Mov instruction, and every output is collected in the order it was
+// produced. Alternatively, the machine can be run against any other Device
+pub struct Machine {
+    memory: Memory,
+    ip: usize,
+    relative_base: Word,
+    status: Status,
+    io: Queue,
+    decoded: DecodeCache,
+    // Used in place of the standard instructions, if set
+    instruction_set: Option<Arc<InstructionSet>>,
+}
+
+#[test]
+fn test_step() {
+    let mut machine = Machine::new(&[1002,4,3,4,33]);
+    assert_eq!(machine.step(), Ok(Status::Running));
+    assert_eq!(machine.ip(), 4);
+    assert_eq!(machine.memory().as_slice(), &[1002,4,3,4,99]);
+    assert_eq!(machine.step(), Ok(Status::Halted));
+    assert_eq!(machine.step(), Ok(Status::Halted));
+}
+
+#[test]
+fn test_step_errors() {
+    let mut machine = Machine::new(&[1101,1,1,5,42]);
+    assert_eq!(machine.step(), Ok(Status::Running));
+    assert_eq!(
+        machine.step(),
+        Err(IntcodeError::InvalidOpcode { ip: 4, instruction: 42 })
+    );
+    // The machine stays put, so the failure can be inspected
+    assert_eq!(machine.ip(), 4);
+
+    let mut machine = Machine::new(&[301,1,1,5]);
+    assert_eq!(
+        machine.step(),
+        Err(IntcodeError::InvalidMode { ip: 0, instruction: 301, mode: 3 })
+    );
+
+    let mut machine = Machine::new(&[1,-1,1,5]);
+    assert_eq!(
+        machine.step(),
+        Err(IntcodeError::NegativeAddress { ip: 0, instruction: 1, address: -1 })
+    );
+
+    let mut machine = Machine::new(&[11101,1,1,5]);
+    assert_eq!(
+        machine.step(),
+        Err(IntcodeError::WriteToImmediate { ip: 0, instruction: 11101 })
+    );
+
+    let mut machine = Machine::new(&[1102,Word::MAX,2,5]);
+    assert_eq!(
+        machine.step(),
+        Err(IntcodeError::Overflow { ip: 0, instruction: 1102 })
+    );
+}
+
+#[test]
+fn test_run() {
+    let mut machine = Machine::new(&[3,0,4,0,99]);
+    assert_eq!(machine.run(), Ok(Status::WaitingForInput));
+    assert_eq!(machine.ip(), 0);
+    machine.push_input(42);
+    assert_eq!(machine.run(), Ok(Status::Halted));
+    assert_eq!(machine.output(), &[42]);
+}
+
+#[test]
+fn test_input_queue() {
+    // Reads two inputs and outputs their sum, then echoes a third
+    let mut machine = Machine::new(&[3,15,3,16,1,15,16,17,4,17,3,15,4,15,99,0,0,0]);
+    machine.extend_input(vec![3, 4]);
+    assert_eq!(machine.run(), Ok(Status::WaitingForInput));
+    assert_eq!(machine.output(), &[7]);
+    machine.push_input(5);
+    assert_eq!(machine.run(), Ok(Status::Halted));
+    assert_eq!(machine.take_output(), vec![7, 5]);
+    assert!(machine.output().is_empty());
+}
+
+#[test]
+fn test_snapshot() {
+    // Outputs double its input, forever
+    let tape = [3,9,1002,9,2,9,4,9,1105,1,0];
+    let mut machine = Machine::new(&tape);
+    machine.extend_input(vec![1, 2]);
+    machine.run_until_output().unwrap();
+    let snapshot = machine.snapshot();
+
+    machine.push_input(3);
+    assert_eq!(machine.run(), Ok(Status::WaitingForInput));
+    assert_eq!(machine.output(), &[2, 4, 6]);
+
+    machine.restore(&snapshot);
+    assert_eq!(machine.output(), &[2]);
+    assert_eq!(machine.pending_input(), &[2]);
+    machine.push_input(10);
+    assert_eq!(machine.run(), Ok(Status::WaitingForInput));
+    assert_eq!(machine.output(), &[2, 4, 20]);
+    assert_eq!(Machine::from_snapshot(&snapshot).snapshot(), snapshot);
+}
+
+#[test]
+fn test_self_modifying() {
+    // Outputs the operand at address 1, then increments it, until it is 7.
+    // Each time around, the OUT instruction has to be decoded afresh
+    let tape = [104,5,1001,1,1,1,1008,1,7,14,1006,14,0,99,0];
+    assert_eq!(run_program(&tape, &[]), Ok(vec![5, 6]));
+}
+
+#[test]
+fn test_run_until_output() {
+    let mut machine = Machine::new(&[104,1,104,2,99]);
+    assert_eq!(machine.run_until_output(), Ok(Status::Output(1)));
+    assert_eq!(machine.run_until_output(), Ok(Status::Output(2)));
+    assert_eq!(machine.run_until_output(), Ok(Status::Halted));
+}
+impl Machine {
+    pub fn new(tape: &[Word]) -> Machine {
+        Machine {
+            memory: Memory::from(tape),
+            ip: 0,
+            relative_base: 0,
+            status: Status::Running,
+            io: Queue::default(),
+            decoded: DecodeCache::default(),
+            instruction_set: None,
         }
-        output
     }
 
-    // Given an opcode, mutable reference to a program, and current position within that program:
-    // mutates the program according the instruction given
-    // If there is an output from the instruction, returns Some(output)
-    // otherwise returns None
-    fn run_instruction(opcode: &Opcode, program: &mut Vec<i32>, pos: &mut usize, input: Option<i32>) -> Option<i32> {
-        let mut args: Vec<i32> = get_args(&program, &pos);
+    pub fn memory(&self) -> &Memory {
+        &self.memory
+    }
+
+    // Writes a value straight into memory, e.g. to set a program's inputs
+    // before running it, without recording the address as touched
+    pub fn patch(&mut self, address: usize, value: Word) {
+        self.memory.set(address, value);
+        self.decoded.invalidate(address);
+    }
+
+    pub fn into_memory(self) -> Memory {
+        self.memory
+    }
+
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    pub fn relative_base(&self) -> Word {
+        self.relative_base
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn output(&self) -> &[Word] {
+        &self.io.output
+    }
+
+    // Hands over everything output so far, leaving the machine's output empty
+    pub fn take_output(&mut self) -> Vec<Word> {
+        std::mem::take(&mut self.io.output)
+    }
+
+    // Input which has been provided but not yet read by the program
+    pub fn pending_input(&self) -> &VecDeque<Word> {
+        &self.io.input
+    }
+
+    pub fn push_input(&mut self, value: Word) {
+        self.io.input.push_back(value);
+    }
+
+    pub fn extend_input<I: IntoIterator<Item = Word>>(&mut self, values: I) {
+        self.io.input.extend(values);
+    }
+
+    // A copy of everything needed to carry on from where the machine is now:
+    // memory, registers and any input or output still queued up
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            memory: self.memory.clone(),
+            ip: self.ip,
+            relative_base: self.relative_base,
+            status: self.status,
+            input: self.io.input.clone(),
+            output: self.io.output.clone(),
+        }
+    }
 
-        let dest: usize = match writes_to_program(opcode) {
-            true  => args.pop().unwrap() as usize,
-            false => 0,
+    // Puts the machine back into the state it was in when a snapshot was taken
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.memory = snapshot.memory.clone();
+        self.ip = snapshot.ip;
+        self.relative_base = snapshot.relative_base;
+        self.status = snapshot.status;
+        self.io = Queue {
+            input: snapshot.input.clone(),
+            output: snapshot.output.clone(),
         };
-        let mut pos_changed = false;
-
-        let mut output: Option<i32> = None;
-        match opcode {
-            Opcode::Add => { program[dest] = args[0] + args[1] },
-            Opcode::Mul => { program[dest] = args[0] * args[1] },
-            Opcode::Mov => { program[dest] = input.unwrap() },
-            Opcode::Out => { output = args.pop() },
-            Opcode::Jnz => {
-                if args[0] != 0 {
-                    *pos = args[1] as usize;
-                    pos_changed = true;
-                }
-            },
-            Opcode::Jz  => {
-                if args[0] == 0 {
-                    *pos = args[1] as usize;
-                    pos_changed = true;
-                }
-            },
-            Opcode::Lt  => {
-                if args[0] < args[1] {
-                    program[dest] = 1
-                } else {
-                    program[dest] = 0
-                }
-            },
-            Opcode::Eq  => {
-                if args[0] == args[1] {
-                    program[dest] = 1
-                } else {
-                    program[dest] = 0
-                }
-            },
-            _ => { panic!("Invalid opcode value {:?}", opcode) },
+        self.decoded.clear();
+    }
+
+    pub fn from_snapshot(snapshot: &Snapshot) -> Machine {
+        let mut machine = Machine::new(&[]);
+        machine.restore(snapshot);
+        machine
+    }
+
+    // Executes a single instruction and returns the resulting status.
+    // If the instruction needs input and none is available, the instruction
+    // pointer is left where it is so the instruction can be retried once
+    // input has been provided. The same goes for an instruction which fails,
+    // so the machine can be inspected afterwards
+    pub fn step(&mut self) -> Result<Status, IntcodeError> {
+        self.step_own_io(None)
+    }
+
+    // As step(), but with input read from and output written to the given
+    // device rather than the machine's own queues
+    pub fn step_with(&mut self, device: &mut dyn Device) -> Result<Status, IntcodeError> {
+        self.execute(device, None)
+    }
+
+    // As step(), but also reports the instruction executed to a trace sink.
+    // Nothing is reported if no instruction ran, i.e. the machine was waiting
+    // for input or had already halted
+    pub fn step_traced(&mut self, sink: &mut dyn TraceSink) -> Result<Status, IntcodeError> {
+        self.step_own_io(Some(sink))
+    }
+
+    // Steps using the machine's own input and output queues as the device
+    fn step_own_io(&mut self, sink: Option<&mut dyn TraceSink>) -> Result<Status, IntcodeError> {
+        let mut io = std::mem::take(&mut self.io);
+        let result = self.execute(&mut io, sink);
+        self.io = io;
+        result
+    }
+
+    fn execute(&mut self, device: &mut dyn Device, sink: Option<&mut dyn TraceSink>) -> Result<Status, IntcodeError> {
+        if self.status == Status::Halted {
+            return Ok(self.status)
         }
-        if !pos_changed {
-            *pos = *pos + num_args(&opcode) + 1;
+        let set = self.instruction_set.clone();
+        let set = set.as_deref().unwrap_or(InstructionSet::builtin());
+        self.status = self.execute_with_set(set, device, sink)?;
+        Ok(self.status)
+    }
+
+    // Decodes the next instruction without executing it, from the machine's
+    // own instruction set if it has one
+    fn decode_next(&mut self) -> Result<Instruction, IntcodeError> {
+        let set = self.instruction_set.as_deref().unwrap_or(InstructionSet::builtin());
+        self.decoded.get_with(&self.memory, self.ip, set)
+    }
+
+    // Keeps stepping until the machine halts or needs input, optionally
+    // stopping early on output and optionally tracing each instruction
+    fn run_loop(&mut self, until_output: bool, mut sink: Option<&mut dyn TraceSink>) -> Result<Status, IntcodeError> {
+        let mut io = std::mem::take(&mut self.io);
+        let result = self.run_loop_with(&mut io, until_output, &mut sink);
+        self.io = io;
+        result
+    }
+
+    fn run_loop_with(
+        &mut self,
+        device: &mut dyn Device,
+        until_output: bool,
+        sink: &mut Option<&mut dyn TraceSink>,
+    ) -> Result<Status, IntcodeError> {
+        loop {
+            let status = match sink {
+                Some(ref mut x) => self.execute(device, Some(*x))?,
+                None            => self.execute(device, None)?,
+            };
+            match status {
+                Status::Running                     => {},
+                Status::Output(_) if !until_output  => {},
+                status                              => { return Ok(status) },
+            }
         }
-        return output
-    }
-
-    fn num_args(opcode: &Opcode) -> usize {
-        match opcode {
-            Opcode::Stop => 0,
-            Opcode::Add => 3,
-            Opcode::Mul => 3,
-            Opcode::Mov => 1,
-            Opcode::Out => 1,
-            Opcode::Jnz => 2,
-            Opcode::Jz  => 2,
-            Opcode::Lt  => 3,
-            Opcode::Eq  => 3,
+    }
+
+    // Runs until the program halts or needs input that it doesn't have
+    pub fn run(&mut self) -> Result<Status, IntcodeError> {
+        self.run_loop(false, None)
+    }
+
+    // As run(), but with input read from and output written to the given
+    // device. Stops if the device has no input to give
+    pub fn run_with(&mut self, device: &mut dyn Device) -> Result<Status, IntcodeError> {
+        self.run_loop_with(device, false, &mut None)
+    }
+
+    // As run(), reporting every instruction executed to a trace sink
+    pub fn run_traced(&mut self, sink: &mut dyn TraceSink) -> Result<Status, IntcodeError> {
+        self.run_loop(false, Some(sink))
+    }
+
+    // As run_with(), reporting every instruction executed to a trace sink
+    pub fn run_with_traced(&mut self, device: &mut dyn Device, sink: &mut dyn TraceSink) -> Result<Status, IntcodeError> {
+        self.run_loop_with(device, false, &mut Some(sink))
+    }
+
+    // Runs until the program produces an output, halts, or needs input
+    pub fn run_until_output(&mut self) -> Result<Status, IntcodeError> {
+        self.run_loop(true, None)
+    }
+
+    // As run(), but fails with StepLimit or InfiniteLoop if the program runs
+    // for too long or gets stuck in a loop. In either case the machine is left
+    // at the next instruction it would have run, so it can be inspected or
+    // given more time
+    pub fn run_limited(&mut self, limits: &Limits) -> Result<Status, IntcodeError> {
+        let mut steps = 0;
+        // State hash -> the step at which it was seen
+        let mut seen: HashMap<u64, usize> = HashMap::new();
+        // When a state hash comes round again after length steps, a copy of
+        // the state along with the length. Hashes can collide, so it's only
+        // a loop if the same state comes round again after another length
+        // steps; either way that's when the suspect is dropped
+        let mut suspect: Option<(usize, Word, Memory, usize)> = None;
+        let mut due = 0;
+        loop {
+            let ip = self.ip;
+            let instruction = self.memory.get(ip);
+            if limits.max_steps == Some(steps) {
+                return Err(IntcodeError::StepLimit { ip, instruction, steps })
+            }
+            if steps == due {
+                if let Some((old_ip, relative_base, memory, length)) = suspect.take() {
+                    if (old_ip, relative_base) == (ip, self.relative_base) && memory.same_values(&self.memory) {
+                        return Err(IntcodeError::InfiniteLoop { ip, instruction, length })
+                    }
+                }
+            }
+            if limits.detect_loops {
+                let mut hasher = DefaultHasher::new();
+                (ip, self.relative_base, &self.memory).hash(&mut hasher);
+                match seen.insert(hasher.finish(), steps) {
+                    Some(first) if suspect.is_none() => {
+                        let length = steps - first;
+                        suspect = Some((ip, self.relative_base, self.memory.clone(), length));
+                        due = steps + length;
+                    },
+                    _ => {},
+                }
+            }
+
+            let pending = self.io.input.len();
+            let status = self.step()?;
+            steps += 1;
+            match status {
+                Status::Running   => {},
+                Status::Output(_) => {
+                    seen.clear();
+                    suspect = None;
+                },
+                status            => { return Ok(status) },
+            }
+            if self.io.input.len() != pending {
+                seen.clear();
+                suspect = None;
+            }
         }
     }
+}
+
+#[test]
+fn test_run_program_relative() {
+    // Quine from day 9: uses relative mode to output a copy of itself
+    let tape = [109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99];
+    assert_eq!(run_program(&tape, &[]).unwrap(), tape.to_vec());
+
+    // Relative mode for a write destination: stores input at rb + 5
+    let tape = [109,10,203,5,4,15,99];
+    assert_eq!(run_program(&tape, &[42]).unwrap(), vec![42]);
+}
+
+#[test]
+fn test_run_program_large_memory() {
+    // Reads and writes well beyond the end of the tape
+    let tape = [1101,2,3,5000000,1,5000000,7000,7000,4,7000,99];
+    assert_eq!(run_program(&tape, &[]).unwrap(), vec![5]);
+}
+
+#[test]
+fn test_run_program_large_numbers() {
+    // From day 9: outputs a 16-digit number, then a large literal
+    let tape = [1102,34915192,34915192,7,4,7,99,0];
+    assert_eq!(run_program(&tape, &[]).unwrap(), vec![1219070632396864]);
+    let tape = [104,1125899906842624,99];
+    assert_eq!(run_program(&tape, &[]).unwrap(), vec![1125899906842624]);
+}
+
+#[test]
+fn test_run_program_overflow() {
+    let tape = [1102,4611686018427387904,2,0,99];
+    assert_eq!(
+        run_program(&tape, &[]),
+        Err(IntcodeError::Overflow { ip: 0, instruction: 1102 })
+    );
+}
+
+#[test]
+fn test_run_program() {
+    // Outputs 1 if the input is 8, otherwise 0
+    let tape = [3,9,8,9,10,9,4,9,99,-1,8];
+    assert_eq!(run_program(&tape, &[8]).unwrap(), vec![1]);
+    assert_eq!(run_program(&tape, &[7]).unwrap(), vec![0]);
+    assert_eq!(
+        run_program(&tape, &[]),
+        Err(IntcodeError::InputExhausted { ip: 0, instruction: 3 })
+    );
+}
+pub fn run_program(tape: &[Word], input: &[Word]) -> Result<Vec<Word>, IntcodeError> {
+    // Runs an Intcode program to completion and returns everything it output
+    run_program_with(tape, input, None)
+}
+
+#[test]
+fn test_run_program_traced() {
+    let mut events: Vec<trace::TraceEvent> = Vec::new();
+    let tape = [3,9,8,9,10,9,4,9,99,-1,8];
+    assert_eq!(run_program_traced(&tape, &[8], &mut events), Ok(vec![1]));
+    assert_eq!(
+        events.iter().map(|x| (x.ip, x.opcode)).collect::<Vec<(usize, Option<Opcode>)>>(),
+        vec![(0, Some(Opcode::Mov)), (2, Some(Opcode::Eq)), (6, Some(Opcode::Out)), (8, Some(Opcode::Stop))]
+    );
+    assert_eq!(events[0].input, Some(8));
+    assert_eq!(events[1].args, vec![8, 8, 9]);
+    assert_eq!(events[1].writes, vec![(9, 1)]);
+    assert_eq!(events[2].output, Some(1));
+}
+// As run_program(), reporting every instruction executed to a trace sink
+pub fn run_program_traced(tape: &[Word], input: &[Word], sink: &mut dyn TraceSink) -> Result<Vec<Word>, IntcodeError> {
+    run_program_with(tape, input, Some(sink))
+}
 
-    fn writes_to_program(opcode: &Opcode) -> bool {
-        let writers = vec![
-            Opcode::Add,
-            Opcode::Mul,
-            Opcode::Mov,
-            Opcode::Lt,
-            Opcode::Eq,
-        ];
-        if writers.contains(opcode) {
-            return true
+fn run_program_with(tape: &[Word], input: &[Word], sink: Option<&mut dyn TraceSink>) -> Result<Vec<Word>, IntcodeError> {
+    let mut machine = Machine::new(tape);
+    machine.extend_input(input.iter().copied());
+    let status = machine.run_loop(false, sink)?;
+    finish_program(machine, status)
+}
+
+#[test]
+fn test_run_program_limited() {
+    let countdown = fixtures::COUNTDOWN;
+    let limits = Limits { max_steps: Some(100), detect_loops: true };
+    assert_eq!(run_program_limited(&countdown, &[], &limits), Ok(vec![3, 2, 1]));
+
+    let limits = Limits { max_steps: Some(5), detect_loops: false };
+    assert_eq!(
+        run_program_limited(&countdown, &[], &limits),
+        Err(IntcodeError::StepLimit { ip: 6, instruction: 1001, steps: 5 })
+    );
+
+    // Jumps back and forth between 0 and 3 forever
+    let tape = [1105,1,3,1105,1,0];
+    let limits = Limits { max_steps: None, detect_loops: true };
+    assert_eq!(
+        run_program_limited(&tape, &[], &limits),
+        Err(IntcodeError::InfiniteLoop { ip: 0, instruction: 1105, length: 2 })
+    );
+
+    // Counts up forever: memory never repeats, so only the step limit stops it
+    let tape = [1001,7,1,7,1105,1,0,0];
+    let limits = Limits { max_steps: Some(1000), detect_loops: true };
+    assert_eq!(
+        run_program_limited(&tape, &[], &limits),
+        Err(IntcodeError::StepLimit { ip: 0, instruction: 1001, steps: 1000 })
+    );
+
+    // Reading input each time around isn't a loop
+    let tape = [3,6,1105,1,0,0,0];
+    let limits = Limits { max_steps: None, detect_loops: true };
+    assert_eq!(
+        run_program_limited(&tape, &[5, 5, 5], &limits),
+        Err(IntcodeError::InputExhausted { ip: 0, instruction: 3 })
+    );
+}
+// As run_program(), but giving up on programs which run for too long or loop
+// forever, as described by the limits
+pub fn run_program_limited(tape: &[Word], input: &[Word], limits: &Limits) -> Result<Vec<Word>, IntcodeError> {
+    let mut machine = Machine::new(tape);
+    machine.extend_input(input.iter().copied());
+    let status = machine.run_limited(limits)?;
+    finish_program(machine, status)
+}
+
+#[test]
+fn test_run_patched() {
+    assert_eq!(run_patched(&[1,0,0,0,99], &[]).unwrap().as_slice(), &[2,0,0,0,99]);
+    assert_eq!(run_patched(&[2,4,4,5,99,0], &[]).unwrap().as_slice(), &[2,4,4,5,99,9801]);
+    // Patching the first instruction's operands so it adds [5] and [6]
+    assert_eq!(run_patched(&[1,0,0,0,99,30,12], &[(1, 5), (2, 6)]).unwrap().get(0), 42);
+    assert_eq!(
+        run_patched(&[3,0,99], &[]).unwrap_err(),
+        IntcodeError::InputExhausted { ip: 0, instruction: 3 }
+    );
+}
+// Runs a program to completion with no input, after writing each (address,
+// value) patch into its memory (e.g. day 2's noun and verb), and returns its
+// final memory
+pub fn run_patched(tape: &[Word], patches: &[(usize, Word)]) -> Result<Memory, IntcodeError> {
+    let mut machine = Machine::new(tape);
+    for (address, value) in patches.iter() {
+        machine.patch(*address, *value);
+    }
+    let status = machine.run()?;
+    check_finished(&machine, status)?;
+    Ok(machine.into_memory())
+}
+
+// How far past the end of a tape patch_tape() will grow it. Anything further
+// is almost certainly a typo, and would allocate a huge tape of zeroes
+pub const MAX_PATCH_GROWTH: usize = 1 << 16;
+
+// An address=value patch which couldn't be applied by patch_tape()
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct PatchError {
+    pub patch: String,
+    pub message: String,
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid patch {:?}: {}", self.patch, self.message)
+    }
+}
+
+impl Error for PatchError {}
+
+#[test]
+fn test_patch_tape() {
+    let mut tape = vec![1,0,0,3,99];
+    assert_eq!(patch_tape(&mut tape, &["1=12", "2=2"]), Ok(()));
+    assert_eq!(tape, vec![1,12,2,3,99]);
+    assert_eq!(patch_tape(&mut tape, &["7=-1".to_string()]), Ok(()));
+    assert_eq!(tape, vec![1,12,2,3,99,0,0,-1]);
+
+    let error = |patch: &str, message: &str| {
+        Err(PatchError { patch: patch.to_string(), message: message.to_string() })
+    };
+    assert_eq!(patch_tape(&mut tape, &["12"]), error("12", "expected address=value"));
+    assert_eq!(patch_tape(&mut tape, &["-1=2"]), error("-1=2", "invalid address \"-1\""));
+    assert_eq!(patch_tape(&mut tape, &["1=x"]), error("1=x", "invalid value \"x\""));
+    assert_eq!(
+        patch_tape(&mut tape, &["100000000000=1"]),
+        error("100000000000=1", "address 100000000000 is too far past the end of the tape (length 8)")
+    );
+    // Patches before a bad one are still applied
+    assert!(patch_tape(&mut tape, &["0=2", "oops"]).is_err());
+    assert_eq!(tape, vec![2,12,2,3,99,0,0,-1]);
+}
+// Applies address=value patches (as given on the command line to the Intcode
+// tools) to a tape, growing it if a patch is past the end, by no more than
+// MAX_PATCH_GROWTH
+pub fn patch_tape<S: AsRef<str>>(tape: &mut Vec<Word>, patches: &[S]) -> Result<(), PatchError> {
+    for patch in patches.iter() {
+        let patch = patch.as_ref();
+        let error = |message: String| PatchError { patch: patch.to_string(), message };
+        let (address, value) = patch.split_once('=')
+            .ok_or_else(|| error("expected address=value".to_string()))?;
+        let address: usize = address.trim().parse()
+            .map_err(|_| error(format!("invalid address {:?}", address)))?;
+        let value: Word = value.trim().parse()
+            .map_err(|_| error(format!("invalid value {:?}", value)))?;
+        if address >= tape.len() {
+            if address - tape.len() >= MAX_PATCH_GROWTH {
+                let message = format!("address {} is too far past the end of the tape (length {})", address, tape.len());
+                return Err(error(message))
+            }
+            tape.resize(address + 1, 0);
         }
-        false
+        tape[address] = value;
     }
+    Ok(())
+}
 
-    #[test]
-    fn test_get_args() {
-        let program = &vec![1002,4,3,4,33];
-        let expected = vec![33,3,4];
-        assert_eq!(get_args(program, &0), expected);
+// Fails if a program which should have run to completion stopped for want of
+// input
+fn check_finished(machine: &Machine, status: Status) -> Result<(), IntcodeError> {
+    match status {
+        Status::WaitingForInput => {
+            Err(IntcodeError::InputExhausted {
+                ip: machine.ip(),
+                instruction: machine.memory().get(machine.ip()),
+            })
+        },
+        _ => Ok(()),
     }
-    fn get_args(program: &Vec<i32>, pos: &usize) -> Vec<i32> {
-        let raw_value = program[*pos];
-        let opcode = get_opcode(&raw_value);
-        if opcode == Opcode::Stop {
-            panic!("Called get_args() for opcode STOP, this should never happen")
+}
+
+// Collects the output of a program run to completion
+fn finish_program(mut machine: Machine, status: Status) -> Result<Vec<Word>, IntcodeError> {
+    check_finished(&machine, status)?;
+    Ok(machine.take_output())
+}
+
+#[test]
+fn test_check_diagnostics() {
+    assert_eq!(check_diagnostics(&[0,0,0,1234]), 1234);
+}
+#[test]
+#[should_panic(expected = "TEST failed")]
+fn test_check_diagnostics_failure() {
+    check_diagnostics(&[0,3,0,1234]);
+}
+// Diagnostic programs (e.g. day 5's TEST) report the result of each test as
+// an output, where anything but zero is a failure, then finish with a
+// diagnostic code. Checks the tests all passed and returns the code
+pub fn check_diagnostics(output: &[Word]) -> Word {
+    let (code, results) = match output.split_last() {
+        Some(x) => x,
+        None    => { panic!("TEST failed: no output") },
+    };
+    if let Some(x) = results.iter().find(|x| **x != 0) {
+        panic!("TEST failed: invalid output {:?}", x)
+    }
+    *code
+}
+
+fn num_args(opcode: &Opcode) -> usize {
+    opcode.info().num_args
+}
+
+// Whether the opcode's last argument is an address to write to
+fn writes_to_program(opcode: &Opcode) -> bool {
+    opcode.info().writes
+}
+
+#[test]
+fn test_to_address() {
+    assert_eq!(to_address(0, 4, 1101), Ok(0));
+    // Too large for a usize only where a usize is narrower than a Word
+    match usize::try_from(Word::MAX) {
+        Ok(x)  => assert_eq!(to_address(Word::MAX, 4, 1101), Ok(x)),
+        Err(_) => assert_eq!(
+            to_address(Word::MAX, 4, 1101),
+            Err(IntcodeError::OutOfBounds { ip: 4, instruction: 1101, address: Word::MAX })
+        ),
+    }
+    assert_eq!(
+        to_address(-1, 4, 1101),
+        Err(IntcodeError::NegativeAddress { ip: 4, instruction: 1101, address: -1 })
+    );
+}
+// Converts a value into a memory address on behalf of the instruction at ip
+fn to_address(value: Word, ip: usize, instruction: Word) -> Result<usize, IntcodeError> {
+    if value < 0 {
+        return Err(IntcodeError::NegativeAddress { ip, instruction, address: value })
+    }
+    usize::try_from(value)
+        .map_err(|_| IntcodeError::OutOfBounds { ip, instruction, address: value })
+}
+
+#[test]
+fn test_get_mode() {
+    assert_eq!(get_mode(0), Some(ParameterMode::Position));
+    assert_eq!(get_mode(1), Some(ParameterMode::Immediate));
+    assert_eq!(get_mode(2), Some(ParameterMode::Relative));
+    assert_eq!(get_mode(3), None);
+}
+fn get_mode(mode_digit: Word) -> Option<ParameterMode> {
+    match mode_digit {
+        0 => Some(ParameterMode::Position),
+        1 => Some(ParameterMode::Immediate),
+        2 => Some(ParameterMode::Relative),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_get_modes() {
+    assert_eq!(
+        get_modes(21002, 3),
+        Ok(vec![ParameterMode::Position, ParameterMode::Immediate, ParameterMode::Relative])
+    );
+    assert_eq!(get_modes(104, 1), Ok(vec![ParameterMode::Immediate]));
+    assert_eq!(get_modes(1301, 3), Err(3));
+}
+// Decodes the parameter modes for each argument of an instruction
+// If any mode digit is invalid, returns it as the error
+fn get_modes(raw_value: Word, num_args: usize) -> Result<Vec<ParameterMode>, Word> {
+    let mut mode_digits = raw_value / 100;
+    let mut modes = Vec::with_capacity(num_args);
+
+    for _ in 0..num_args {
+        match get_mode(mode_digits % 10) {
+            Some(x) => modes.push(x),
+            None    => { return Err(mode_digits % 10) },
         }
-        let num_args = num_args(&opcode);
-
-        let mut mode_digits = raw_value / 100;
-        let mut args = Vec::new();
-
-        for i in 1..(num_args + 1) {
-            let mode: ParameterMode = match i {
-                // Any instruction that writes to a location simply treats the
-                // last argument as a destination address.
-                // The spec says this is "position" mode but since we use it as
-                // an index after reading, we actually treat it as immediate
-                x if x == num_args && writes_to_program(&opcode) => ParameterMode::Immediate,
-                _ if mode_digits % 10 == 0                       => ParameterMode::Position,
-                _ if mode_digits % 10 == 1                       => ParameterMode::Immediate,
-                _                                                => {
-                    panic!("Unsupported parameter mode indicator {:?}", mode_digits % 10)
-                },
-            };
-            match mode {
-                ParameterMode::Position => {
-                    let arg_addr = program[pos + i] as usize;
-                    args.push(program[arg_addr]);
-                },
-                ParameterMode::Immediate => {
-                    args.push(program[pos + i]);
+        mode_digits /= 10
+    }
+    Ok(modes)
+}
+
+// Tapes and other fixtures shared by tests throughout the Intcode modules
+#[cfg(test)]
+mod fixtures {
+    use std::io;
+    use std::io::Write;
+
+    use super::Word;
+
+    // Counts down from 3 using address 14, outputting each number
+    pub const COUNTDOWN: [Word; 15] = [1101,3,0,14,4,14,1001,14,-1,14,1005,14,4,99,0];
+
+    // Echoes its input doubled, until it reads a zero
+    pub const ECHO: [Word; 17] = [3,15,1006,15,14,1002,15,2,16,4,16,1105,1,0,99,0,0];
+
+    // A writer which accepts the given number of lines, then fails
+    #[derive(Debug)]
+    pub struct Full(pub usize);
+
+    impl Write for Full {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            match self.0 {
+                0 => Err(io::Error::new(io::ErrorKind::WriteZero, "disk full")),
+                _ => {
+                    self.0 -= buf.iter().filter(|x| **x == b'\n').count();
+                    Ok(buf.len())
                 },
             }
-            mode_digits = mode_digits / 10
         }
-        args
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
     }
 }
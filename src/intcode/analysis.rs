@@ -0,0 +1,343 @@
+// Static analysis of Intcode tapes: which code is reachable from address 0,
+// how it splits into basic blocks, how those blocks jump between each other,
+// and what each cell of the tape is used for.
+//
+// Only jumps to immediate targets can be followed. Jumps to computed targets
+// (position or relative mode) are flagged, and whatever they lead to is
+// invisible here, as is anything accessed in relative mode
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use super::disasm;
+use super::disasm::{Line, Operand};
+use super::{Opcode, ParameterMode, Word};
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Target {
+    Address(usize),
+    // Only known once the program is running
+    Computed,
+}
+
+// How control leaves a basic block
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Exit {
+    // Runs on into the block starting at the given address
+    Next(usize),
+    // A Jnz or Jz, which either jumps to its target or runs on to next
+    Jump { opcode: Opcode, target: Target, next: usize },
+    Halt,
+    // Runs into a value which doesn't decode as an instruction, or off the
+    // end of the tape. The program may have written an instruction there by
+    // the time it gets there
+    Invalid(usize),
+}
+
+// A straight run of instructions from start up to (not including) end
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Block {
+    pub start: usize,
+    pub end: usize,
+    pub exit: Exit,
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum EdgeKind {
+    Next,
+    Taken,
+    NotTaken,
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub kind: EdgeKind,
+}
+
+// What a cell of the tape is used for, as far as can be seen statically
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Cell {
+    // Nothing reachable refers to it
+    Unused,
+    Code,
+    // Read or written by reachable code
+    Data,
+    // Part of an instruction (or where one is expected) which reachable code
+    // writes to
+    SelfModified,
+}
+
+pub struct Analysis {
+    // In address order
+    pub blocks: Vec<Block>,
+    pub cells: Vec<Cell>,
+}
+
+// Where a jump's target operand sends it. Negative immediate targets would
+// fail at runtime, so are treated as computed rather than followed
+pub fn jump_target(operand: &Operand) -> Target {
+    match operand.mode {
+        ParameterMode::Immediate if operand.value >= 0 => Target::Address(operand.value as usize),
+        _ => Target::Computed,
+    }
+}
+
+fn decode(tape: &[Word], address: usize) -> Option<(Opcode, Vec<Operand>)> {
+    match disasm::decode(tape, address)? {
+        Line::Instruction { opcode, operands, .. } => Some((opcode, operands)),
+        Line::Data { .. }                          => None,
+    }
+}
+
+#[test]
+fn test_leaders() {
+    let countdown = super::fixtures::COUNTDOWN;
+    assert_eq!(leaders(&countdown).into_iter().collect::<Vec<usize>>(), vec![0, 4, 13]);
+}
+// Finds the start of every basic block reachable from address 0: address 0
+// itself, the immediate target of every jump, and the instruction after every
+// jump. Jump targets off the end of the tape aren't included
+pub fn leaders(tape: &[Word]) -> BTreeSet<usize> {
+    let mut leaders = BTreeSet::new();
+    let mut visited = BTreeSet::new();
+    let mut pending = vec![0];
+    leaders.insert(0);
+
+    while let Some(mut address) = pending.pop() {
+        while visited.insert(address) {
+            let (opcode, operands) = match decode(tape, address) {
+                Some(x) => x,
+                None    => break,
+            };
+            let next = address + operands.len() + 1;
+            match opcode {
+                Opcode::Stop => break,
+                Opcode::Jnz | Opcode::Jz => {
+                    match jump_target(&operands[1]) {
+                        Target::Address(x) if x < tape.len() => {
+                            leaders.insert(x);
+                            pending.push(x);
+                        },
+                        _ => {},
+                    }
+                    leaders.insert(next);
+                },
+                _ => {},
+            }
+            address = next;
+        }
+    }
+    leaders
+}
+
+// Splits reachable code into basic blocks, in address order. A block ends
+// after a jump or STOP, before the next block starts, or at a value which
+// doesn't decode as an instruction
+pub fn blocks(tape: &[Word]) -> Vec<Block> {
+    let leaders = leaders(tape);
+    let mut blocks = Vec::new();
+    for start in leaders.iter() {
+        let mut address = *start;
+        let exit = loop {
+            let (opcode, operands) = match decode(tape, address) {
+                Some(x) => x,
+                None    => break Exit::Invalid(address),
+            };
+            let next = address + operands.len() + 1;
+            address = next;
+            match opcode {
+                Opcode::Stop => break Exit::Halt,
+                Opcode::Jnz | Opcode::Jz => {
+                    break Exit::Jump { opcode, target: jump_target(&operands[1]), next }
+                },
+                _ if leaders.contains(&next) => break Exit::Next(next),
+                _ => {},
+            }
+        };
+        blocks.push(Block { start: *start, end: address, exit });
+    }
+    blocks
+}
+
+#[test]
+fn test_analyse() {
+    // Reads a number and outputs it, skipping the computed jump at 9 if it's
+    // 10. The ADD at 14 writes over the operand of the OUT at 18
+    let tape = [3,21,1008,21,10,22,1005,22,12,6,23,24,4,21,1101,1,1,19,104,0,99,0,0,0,0,7];
+    let analysis = analyse(&tape);
+    assert_eq!(
+        analysis.blocks,
+        vec![
+            Block { start: 0, end: 9, exit: Exit::Jump { opcode: Opcode::Jnz, target: Target::Address(12), next: 9 } },
+            Block { start: 9, end: 12, exit: Exit::Jump { opcode: Opcode::Jz, target: Target::Computed, next: 12 } },
+            Block { start: 12, end: 21, exit: Exit::Halt },
+        ]
+    );
+    assert_eq!(
+        analysis.edges(),
+        vec![
+            Edge { from: 0, to: 12, kind: EdgeKind::Taken },
+            Edge { from: 0, to: 9, kind: EdgeKind::NotTaken },
+            Edge { from: 9, to: 12, kind: EdgeKind::NotTaken },
+        ]
+    );
+    assert_eq!(analysis.computed_jumps(), vec![9]);
+
+    let mut cells = vec![Cell::Code; 21];
+    cells[19] = Cell::SelfModified;
+    cells.extend(&[Cell::Data, Cell::Data, Cell::Data, Cell::Data, Cell::Unused]);
+    assert_eq!(analysis.cells, cells);
+
+    let dot = analysis.to_dot(&tape);
+    assert!(dot.contains("    b9 [label=\"0009: JZ [23], [24]\\l\"];\n"));
+    assert!(dot.contains("    b0 -> b12 [label=\"taken\"];\n"));
+    assert!(dot.contains("    b9 -> computed9 [label=\"taken\"];\n"));
+    assert!(dot.contains(", color=red];\n"));
+
+    // Day 5 writes its first input into the instruction at 6, which doesn't
+    // decode until it has, so nothing past it can be seen
+    let tape: Vec<Word> = crate::input_to_vec_t_fromstr(include_str!("../bin/day5/input"), ',');
+    let analysis = analyse(&tape);
+    assert_eq!(analysis.blocks, vec![Block { start: 0, end: 6, exit: Exit::Invalid(6) }]);
+    assert_eq!(analysis.cells[6], Cell::SelfModified);
+    assert_eq!(analysis.cells[225], Cell::Data);
+}
+// Analyses the code reachable from address 0
+pub fn analyse(tape: &[Word]) -> Analysis {
+    let blocks = blocks(tape);
+    let mut cells = vec![Cell::Unused; tape.len()];
+    let mut data = BTreeSet::new();
+    let mut written = BTreeSet::new();
+
+    for block in blocks.iter() {
+        let mut address = block.start;
+        while address < block.end {
+            let (_, operands) = decode(tape, address).unwrap();
+            let next = address + operands.len() + 1;
+            for cell in cells[address..next].iter_mut() {
+                *cell = Cell::Code;
+            }
+            // Relative addresses depend on the relative base at runtime
+            for operand in operands.iter().filter(|x| x.mode == ParameterMode::Position && x.value >= 0) {
+                data.insert(operand.value as usize);
+                if operand.is_dest {
+                    written.insert(operand.value as usize);
+                }
+            }
+            address = next;
+        }
+    }
+
+    let expected: BTreeSet<usize> = blocks.iter()
+        .filter_map(|x| match x.exit {
+            Exit::Invalid(address) => Some(address),
+            _                      => None,
+        })
+        .collect();
+    for address in data.iter() {
+        if let Some(cell @ Cell::Unused) = cells.get_mut(*address) {
+            *cell = Cell::Data;
+        }
+    }
+    for address in written.iter() {
+        match cells.get_mut(*address) {
+            Some(cell @ Cell::Code) => *cell = Cell::SelfModified,
+            Some(cell) if expected.contains(address) => *cell = Cell::SelfModified,
+            _ => {},
+        }
+    }
+    Analysis { blocks, cells }
+}
+
+impl Analysis {
+    // Edges between blocks, in block order. Jumps to computed targets have no
+    // edge, as there's no telling where they go
+    pub fn edges(&self) -> Vec<Edge> {
+        let mut edges = Vec::new();
+        for block in self.blocks.iter() {
+            let from = block.start;
+            match block.exit {
+                Exit::Next(to) => edges.push(Edge { from, to, kind: EdgeKind::Next }),
+                Exit::Jump { target, next, .. } => {
+                    if let Target::Address(to) = target {
+                        edges.push(Edge { from, to, kind: EdgeKind::Taken });
+                    }
+                    edges.push(Edge { from, to: next, kind: EdgeKind::NotTaken });
+                },
+                Exit::Halt | Exit::Invalid(_) => {},
+            }
+        }
+        edges
+    }
+
+    // Start of every block which ends in a jump to a computed target
+    pub fn computed_jumps(&self) -> Vec<usize> {
+        self.blocks.iter()
+            .filter(|x| matches!(x.exit, Exit::Jump { target: Target::Computed, .. }))
+            .map(|x| x.start)
+            .collect()
+    }
+
+    // Addresses of every cell of the given kind
+    pub fn cells_of(&self, kind: Cell) -> Vec<usize> {
+        (0..self.cells.len()).filter(|x| self.cells[*x] == kind).collect()
+    }
+
+    // Renders the control flow graph in Graphviz DOT format, with each block
+    // listing its code. Blocks containing self-modified cells are drawn in
+    // red, and computed jumps lead to a "?" node
+    pub fn to_dot(&self, tape: &[Word]) -> String {
+        let mut dot = String::new();
+        writeln!(dot, "digraph intcode {{").unwrap();
+        writeln!(dot, "    node [shape=box, fontname=\"monospace\"];").unwrap();
+
+        let starts: BTreeSet<usize> = self.blocks.iter().map(|x| x.start).collect();
+        for block in self.blocks.iter() {
+            let mut label = String::new();
+            let mut address = block.start;
+            while address < block.end {
+                let line = disasm::decode(tape, address).unwrap();
+                address += line.size();
+                write!(label, "{}\\l", line).unwrap();
+            }
+            if let Exit::Invalid(x) = block.exit {
+                write!(label, "{:04}: ???\\l", x).unwrap();
+            }
+            let modified = (block.start..block.end).any(|x| self.cells[x] == Cell::SelfModified)
+                || matches!(block.exit, Exit::Invalid(x) if self.cells.get(x) == Some(&Cell::SelfModified));
+            let colour = if modified { ", color=red" } else { "" };
+            writeln!(dot, "    b{} [label=\"{}\"{}];", block.start, label, colour).unwrap();
+        }
+
+        for edge in self.edges() {
+            let label = match edge.kind {
+                EdgeKind::Next     => "",
+                EdgeKind::Taken    => " [label=\"taken\"]",
+                EdgeKind::NotTaken => " [label=\"not taken\", style=dashed]",
+            };
+            writeln!(dot, "    b{} -> b{}{};", edge.from, edge.to, label).unwrap();
+            if !starts.contains(&edge.to) {
+                writeln!(dot, "    b{} [label=\"{:04}: off the tape\", shape=plaintext];", edge.to, edge.to).unwrap();
+            }
+        }
+        for start in self.computed_jumps() {
+            writeln!(dot, "    computed{} [label=\"?\", shape=circle];", start).unwrap();
+            writeln!(dot, "    b{} -> computed{} [label=\"taken\"];", start, start).unwrap();
+        }
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
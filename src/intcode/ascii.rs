@@ -0,0 +1,57 @@
+// Helpers for programs which talk in ASCII, such as days 17, 21 and 25.
+// Text is sent one character code at a time, and output codes are turned
+// back into text. Values outside the ASCII range aren't characters at all,
+// usually a final answer, so they are kept separately as numbers
+use super::{IntcodeError, Machine, Status, Word};
+
+#[test]
+fn test_encode() {
+    assert_eq!(encode("NOT A J\n"), vec![78,79,84,32,65,32,74,10]);
+    assert_eq!(encode(""), Vec::<Word>::new());
+}
+// Character codes for a string, ready to be used as input. Characters outside
+// ASCII come out as their UTF-8 bytes, which no puzzle expects
+pub fn encode(text: &str) -> Vec<Word> {
+    text.bytes().map(Word::from).collect()
+}
+
+#[test]
+fn test_decode() {
+    assert_eq!(decode(&[72,105,10]), ("Hi\n".to_string(), vec![]));
+    assert_eq!(decode(&[46,35,10,19357,46]), (".#\n.".to_string(), vec![19357]));
+    assert_eq!(decode(&[-1,128]), (String::new(), vec![-1, 128]));
+}
+// Splits output into text and the values which couldn't be text, each kept
+// in the order they were output
+pub fn decode(output: &[Word]) -> (String, Vec<Word>) {
+    let mut text = String::new();
+    let mut values = Vec::new();
+    for x in output.iter() {
+        match *x {
+            0..=127 => text.push(*x as u8 as char),
+            _       => values.push(*x),
+        }
+    }
+    (text, values)
+}
+
+#[test]
+fn test_run() {
+    // Reads characters until a newline, echoing each one back in upper case,
+    // then outputs a hundred times how many it read
+    let tape = [
+        3,100,1008,100,10,101,1005,101,23,1001,100,-32,100,4,100,1001,102,100,102,
+        1105,1,0,0,4,102,99
+    ];
+    let mut machine = Machine::new(&tape);
+    assert_eq!(run(&mut machine, "ab"), Ok((Status::WaitingForInput, "AB".to_string(), vec![])));
+    assert_eq!(run(&mut machine, "c\n"), Ok((Status::Halted, "C".to_string(), vec![300])));
+}
+// Sends a line of text (or several) to a machine and runs it until it halts or
+// wants more input, returning its status along with the decoded output
+pub fn run(machine: &mut Machine, input: &str) -> Result<(Status, String, Vec<Word>), IntcodeError> {
+    machine.extend_input(encode(input));
+    let status = machine.run()?;
+    let (text, values) = decode(&machine.take_output());
+    Ok((status, text, values))
+}
@@ -0,0 +1,352 @@
+// A small assembly language for Intcode, so test programs can be written
+// without hand-encoding mode digits. For example:
+//
+//   ; Counts down from 3, outputting each number
+//           ADD #3, #0, ->count
+//   loop:   OUT [count]
+//           ADD [count], #-1, ->count
+//           JNZ [count], #loop
+//           STOP
+//   count:  .data 0
+//
+// Mnemonics are those shown by the disassembler (see Opcode::mnemonic).
+// Operands are written as #imm, [pos] or rb+n, and any of these can use a
+// label in place of a number. Write destinations may be given a -> prefix,
+// in which case the brackets can be left off. A leading address such as
+// "0012:" is ignored, so disassembler listings can be reassembled as-is
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use super::{num_args, writes_to_program};
+use super::{Opcode, ParameterMode, Word};
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct AsmError {
+    // Line numbers start from 1, as in an editor
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for AsmError {}
+
+fn error<T>(line: usize, message: String) -> Result<T, AsmError> {
+    Err(AsmError { line, message })
+}
+
+// Either a literal number or a reference to a label, which can only be
+// resolved once we know where every label lives
+#[derive(PartialEq)]
+#[derive(Debug)]
+enum Value {
+    Literal(Word),
+    Label(String),
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+struct Operand {
+    mode: ParameterMode,
+    value: Value,
+    is_dest: bool,
+}
+
+// A parsed line of source, which still needs labels resolving
+#[derive(PartialEq)]
+#[derive(Debug)]
+enum Item {
+    Instruction { opcode: Opcode, operands: Vec<Operand> },
+    Data(Vec<Value>),
+}
+
+impl Item {
+    fn size(&self) -> usize {
+        match self {
+            Item::Instruction { operands, .. } => operands.len() + 1,
+            Item::Data(values)                 => values.len(),
+        }
+    }
+}
+
+fn is_label(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        },
+        _ => false,
+    }
+}
+
+fn parse_value(s: &str, line: usize) -> Result<Value, AsmError> {
+    let s = s.trim();
+    if is_label(s) {
+        return Ok(Value::Label(s.to_string()))
+    }
+    match s.parse::<Word>() {
+        Ok(x)  => Ok(Value::Literal(x)),
+        Err(_) => error(line, format!("invalid value {:?}", s)),
+    }
+}
+
+// The offset in a relative operand such as "rb", "rb+2" or "rb - 1", or None
+// if the operand isn't relative. A label which merely starts with "rb", such
+// as "rbuf", isn't relative
+fn relative_offset(s: &str) -> Option<&str> {
+    let x = s.strip_prefix("rb")?.trim();
+    match x.chars().next() {
+        None | Some('+') | Some('-') => Some(x),
+        _                            => None,
+    }
+}
+
+#[test]
+fn test_parse_operand() {
+    let operand = |s| parse_operand(s, 1).unwrap();
+    assert_eq!(
+        operand("#-6"),
+        Operand { mode: ParameterMode::Immediate, value: Value::Literal(-6), is_dest: false }
+    );
+    assert_eq!(
+        operand("[count]"),
+        Operand { mode: ParameterMode::Position, value: Value::Label("count".to_string()), is_dest: false }
+    );
+    assert_eq!(
+        operand("rb-3"),
+        Operand { mode: ParameterMode::Relative, value: Value::Literal(-3), is_dest: false }
+    );
+    assert_eq!(
+        operand("->225"),
+        Operand { mode: ParameterMode::Position, value: Value::Literal(225), is_dest: true }
+    );
+    assert_eq!(
+        operand("->rb+1"),
+        Operand { mode: ParameterMode::Relative, value: Value::Literal(1), is_dest: true }
+    );
+    assert_eq!(
+        operand("->rbuf"),
+        Operand { mode: ParameterMode::Position, value: Value::Label("rbuf".to_string()), is_dest: true }
+    );
+    assert_eq!(
+        operand("[rb]"),
+        Operand { mode: ParameterMode::Position, value: Value::Label("rb".to_string()), is_dest: false }
+    );
+    assert!(parse_operand("225", 1).is_err());
+    assert!(parse_operand("rbuf", 1).is_err());
+    assert!(parse_operand("[225", 1).is_err());
+}
+fn parse_operand(s: &str, line: usize) -> Result<Operand, AsmError> {
+    let s = s.trim();
+    let (s, is_dest) = match s.strip_prefix("->") {
+        Some(x) => (x.trim(), true),
+        None    => (s, false),
+    };
+
+    let (mode, value) = if let Some(x) = s.strip_prefix('#') {
+        (ParameterMode::Immediate, parse_value(x, line)?)
+    } else if let Some(x) = s.strip_prefix('[') {
+        match x.strip_suffix(']') {
+            Some(x) => (ParameterMode::Position, parse_value(x, line)?),
+            None    => { return error(line, format!("unclosed bracket in {:?}", s)) },
+        }
+    } else if let Some(x) = relative_offset(s) {
+        let value = if x.is_empty() {
+            Value::Literal(0)
+        } else if let Some(offset) = x.strip_prefix('+') {
+            parse_value(offset, line)?
+        } else {
+            parse_value(x, line)?
+        };
+        (ParameterMode::Relative, value)
+    } else if is_dest {
+        (ParameterMode::Position, parse_value(s, line)?)
+    } else {
+        return error(line, format!("operand {:?} needs a mode: #n, [n] or rb+n", s))
+    };
+    Ok(Operand { mode, value, is_dest })
+}
+
+// Parses a single line of source into any labels it defines, and the item
+// (if any) that it contains
+fn parse_line(source: &str, line: usize) -> Result<(Vec<String>, Option<Item>), AsmError> {
+    let mut rest = match source.find(';') {
+        Some(x) => &source[..x],
+        None    => source,
+    }.trim();
+
+    let mut labels = Vec::new();
+    while let Some(x) = rest.find(':') {
+        let label = rest[..x].trim();
+        if is_label(label) {
+            labels.push(label.to_string());
+        } else if label.parse::<usize>().is_err() {
+            return error(line, format!("invalid label {:?}", label))
+        }
+        rest = rest[x + 1..].trim();
+    }
+    if rest.is_empty() {
+        return Ok((labels, None))
+    }
+
+    let (word, args) = match rest.find(char::is_whitespace) {
+        Some(x) => (&rest[..x], rest[x..].trim()),
+        None    => (rest, ""),
+    };
+    let args: Vec<&str> = match args {
+        "" => Vec::new(),
+        _  => args.split(',').collect(),
+    };
+
+    if word == ".data" {
+        let values = args.iter()
+            .map(|x| parse_value(x, line))
+            .collect::<Result<Vec<Value>, AsmError>>()?;
+        if values.is_empty() {
+            return error(line, ".data needs at least one value".to_string())
+        }
+        return Ok((labels, Some(Item::Data(values))))
+    }
+
+    let opcode = match Opcode::from_mnemonic(word) {
+        Some(x) => x,
+        None    => { return error(line, format!("unknown mnemonic {:?}", word)) },
+    };
+    if args.len() != num_args(&opcode) {
+        return error(line, format!(
+            "{} takes {} operands, found {}", opcode.mnemonic(), num_args(&opcode), args.len()
+        ))
+    }
+    let mut operands = Vec::with_capacity(args.len());
+    for (i, arg) in args.iter().enumerate() {
+        let operand = parse_operand(arg, line)?;
+        let should_be_dest = i + 1 == args.len() && writes_to_program(&opcode);
+        if should_be_dest && operand.mode == ParameterMode::Immediate {
+            return error(line, format!("cannot write to immediate operand {:?}", arg.trim()))
+        }
+        if operand.is_dest && !should_be_dest {
+            return error(line, format!("operand {:?} is not a destination", arg.trim()))
+        }
+        operands.push(operand);
+    }
+    Ok((labels, Some(Item::Instruction { opcode, operands })))
+}
+
+fn resolve(value: &Value, labels: &HashMap<String, usize>, line: usize) -> Result<Word, AsmError> {
+    match value {
+        Value::Literal(x) => Ok(*x),
+        Value::Label(x)   => {
+            match labels.get(x) {
+                Some(addr) => Ok(*addr as Word),
+                None       => error(line, format!("undefined label {:?}", x)),
+            }
+        },
+    }
+}
+
+#[test]
+fn test_assemble() {
+    let source = "
+        ; Counts down from 3, outputting each number
+                ADD #3, #0, ->count
+        loop:   OUT [count]
+                ADD [count], #-1, ->count
+                JNZ [count], #loop
+                STOP
+        count:  .data 0
+    ";
+    let tape = assemble(source).unwrap();
+    assert_eq!(tape, super::fixtures::COUNTDOWN);
+    assert_eq!(super::run_program(&tape, &[]), Ok(vec![3,2,1]));
+
+    let tape = assemble("ARB #10\nMOV ->rb+1\nOUT rb+1\nSTOP").unwrap();
+    assert_eq!(tape, vec![109,10,203,1,204,1,99]);
+
+    // A label starting with "rb" is still a position
+    let tape = assemble("MOV ->rbuf\nOUT [rbuf]\nSTOP\nrbuf: .data 0").unwrap();
+    assert_eq!(tape, vec![3,5,4,5,99,0]);
+    assert_eq!(super::run_program(&tape, &[7]), Ok(vec![7]));
+}
+
+#[test]
+fn test_assemble_errors() {
+    assert_eq!(
+        assemble("ADD #1, #2, #3"),
+        Err(AsmError { line: 1, message: "cannot write to immediate operand \"#3\"".to_string() })
+    );
+    assert_eq!(
+        assemble("STOP\nJMP #0"),
+        Err(AsmError { line: 2, message: "unknown mnemonic \"JMP\"".to_string() })
+    );
+    assert_eq!(
+        assemble("OUT [nowhere]"),
+        Err(AsmError { line: 1, message: "undefined label \"nowhere\"".to_string() })
+    );
+    assert!(assemble("x: STOP\nx: STOP").is_err());
+    assert!(assemble("OUT #1, #2").is_err());
+}
+
+#[test]
+fn test_reassemble_listing() {
+    let tape: Vec<Word> = crate::input_to_vec_t_fromstr(include_str!("../bin/day5/input"), ',');
+    let listing = super::disasm::listing(&tape);
+    assert_eq!(assemble(&listing), Ok(tape));
+}
+// Assembles source into a tape which can be loaded into a Machine
+pub fn assemble(source: &str) -> Result<Vec<Word>, AsmError> {
+    // First pass works out where everything goes, so that labels can be
+    // used before they are defined
+    let mut items = Vec::new();
+    let mut labels: HashMap<String, usize> = HashMap::new();
+    let mut address = 0;
+
+    for (i, text) in source.lines().enumerate() {
+        let line = i + 1;
+        let (defined, item) = parse_line(text, line)?;
+        for label in defined {
+            if labels.insert(label.clone(), address).is_some() {
+                return error(line, format!("label {:?} is defined twice", label))
+            }
+        }
+        if let Some(item) = item {
+            address += item.size();
+            items.push((line, item));
+        }
+    }
+
+    let mut tape = Vec::with_capacity(address);
+    for (line, item) in items {
+        match item {
+            Item::Instruction { opcode, operands } => {
+                let mut instruction = opcode.value();
+                let mut place = 100;
+                for operand in operands.iter() {
+                    let mode_digit = match operand.mode {
+                        ParameterMode::Position  => 0,
+                        ParameterMode::Immediate => 1,
+                        ParameterMode::Relative  => 2,
+                    };
+                    instruction += mode_digit * place;
+                    place *= 10;
+                }
+                tape.push(instruction);
+                for operand in operands.iter() {
+                    tape.push(resolve(&operand.value, &labels, line)?);
+                }
+            },
+            Item::Data(values) => {
+                for value in values.iter() {
+                    tape.push(resolve(value, &labels, line)?);
+                }
+            },
+        }
+    }
+    Ok(tape)
+}
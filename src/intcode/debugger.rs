@@ -0,0 +1,207 @@
+// The guts of the intcode-dbg binary: a Machine along with breakpoints and
+// watchpoints, which can be stepped or left to run until one of them trips
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use super::trace::TraceEvent;
+use super::{get_opcode, IntcodeError, Machine, Opcode, Status, Word};
+
+// Why the debugger handed control back
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Stop {
+    // About to execute the instruction at a breakpoint address
+    Breakpoint(usize),
+    // About to execute an instruction with a watched opcode
+    OpcodeBreakpoint(Opcode),
+    // A watched address changed value
+    Watchpoint { address: usize, old: Word, new: Word },
+    // Ran for the requested number of steps without anything else happening
+    Steps,
+    Halted,
+    WaitingForInput,
+}
+
+#[cfg(test)]
+fn countdown() -> Debugger {
+    Debugger::new(Machine::new(&super::fixtures::COUNTDOWN))
+}
+
+#[test]
+fn test_breakpoints() {
+    let mut debugger = countdown();
+    let mut outputs = Vec::new();
+    debugger.add_breakpoint(6);
+
+    let stop = debugger.resume(None, &mut |x| outputs.extend(x.output));
+    assert_eq!(stop, Ok(Stop::Breakpoint(6)));
+    assert_eq!(outputs, vec![3]);
+
+    // Resuming from a breakpoint runs until we come back around to it
+    let stop = debugger.resume(None, &mut |x| outputs.extend(x.output));
+    assert_eq!(stop, Ok(Stop::Breakpoint(6)));
+    assert_eq!(outputs, vec![3, 2]);
+
+    debugger.clear();
+    debugger.add_opcode_breakpoint(Opcode::Stop);
+    let stop = debugger.resume(None, &mut |x| outputs.extend(x.output));
+    assert_eq!(stop, Ok(Stop::OpcodeBreakpoint(Opcode::Stop)));
+    assert_eq!(outputs, vec![3, 2, 1]);
+    assert_eq!(debugger.resume(None, &mut |_| {}), Ok(Stop::Halted));
+}
+
+#[test]
+fn test_watchpoints() {
+    let mut debugger = countdown();
+    debugger.add_watchpoint(14);
+    assert_eq!(
+        debugger.resume(None, &mut |_| {}),
+        Ok(Stop::Watchpoint { address: 14, old: 0, new: 3 })
+    );
+    assert_eq!(
+        debugger.resume(None, &mut |_| {}),
+        Ok(Stop::Watchpoint { address: 14, old: 3, new: 2 })
+    );
+    assert_eq!(debugger.machine.ip(), 10);
+}
+
+#[test]
+fn test_step_limit() {
+    let mut debugger = countdown();
+    assert_eq!(debugger.resume(Some(3), &mut |_| {}), Ok(Stop::Steps));
+    assert_eq!(debugger.machine.ip(), 10);
+
+    let mut debugger = Debugger::new(Machine::new(&[3,0,99]));
+    assert_eq!(debugger.resume(None, &mut |_| {}), Ok(Stop::WaitingForInput));
+    debugger.machine.push_input(5);
+    let (event, stop) = debugger.step().unwrap();
+    assert_eq!(event.unwrap().input, Some(5));
+    assert_eq!(stop, None);
+}
+
+pub struct Debugger {
+    pub machine: Machine,
+    breakpoints: BTreeSet<usize>,
+    opcode_breakpoints: Vec<Opcode>,
+    // Watched addresses, along with the value last seen at each
+    watchpoints: BTreeMap<usize, Word>,
+}
+
+impl Debugger {
+    pub fn new(machine: Machine) -> Debugger {
+        Debugger {
+            machine,
+            breakpoints: BTreeSet::new(),
+            opcode_breakpoints: Vec::new(),
+            watchpoints: BTreeMap::new(),
+        }
+    }
+
+    pub fn breakpoints(&self) -> &BTreeSet<usize> {
+        &self.breakpoints
+    }
+
+    pub fn opcode_breakpoints(&self) -> &[Opcode] {
+        &self.opcode_breakpoints
+    }
+
+    pub fn watchpoints(&self) -> &BTreeMap<usize, Word> {
+        &self.watchpoints
+    }
+
+    pub fn add_breakpoint(&mut self, address: usize) {
+        self.breakpoints.insert(address);
+    }
+
+    pub fn add_opcode_breakpoint(&mut self, opcode: Opcode) {
+        if !self.opcode_breakpoints.contains(&opcode) {
+            self.opcode_breakpoints.push(opcode);
+        }
+    }
+
+    pub fn add_watchpoint(&mut self, address: usize) {
+        self.watchpoints.insert(address, self.machine.memory().get(address));
+    }
+
+    // Removes every breakpoint and watchpoint
+    pub fn clear(&mut self) {
+        self.breakpoints.clear();
+        self.opcode_breakpoints.clear();
+        self.watchpoints.clear();
+    }
+
+    // Whether the next instruction would trip a breakpoint
+    fn breakpoint_at_ip(&self) -> Option<Stop> {
+        let ip = self.machine.ip();
+        if self.breakpoints.contains(&ip) {
+            return Some(Stop::Breakpoint(ip))
+        }
+        match get_opcode(&self.machine.memory().get(ip)) {
+            Some(x) if self.opcode_breakpoints.contains(&x) => Some(Stop::OpcodeBreakpoint(x)),
+            _ => None,
+        }
+    }
+
+    // Checks watched addresses for changes, updating the values we last saw
+    fn changed_watchpoint(&mut self) -> Option<Stop> {
+        let memory = self.machine.memory();
+        let mut stop = None;
+        for (address, old) in self.watchpoints.iter_mut() {
+            let new = memory.get(*address);
+            if new != *old && stop.is_none() {
+                stop = Some(Stop::Watchpoint { address: *address, old: *old, new });
+            }
+            *old = new;
+        }
+        stop
+    }
+
+    // Executes a single instruction, ignoring breakpoints.
+    // Returns the instruction executed (if any), along with a reason to stop
+    // if the machine can't carry on or a watchpoint tripped
+    pub fn step(&mut self) -> Result<(Option<TraceEvent>, Option<Stop>), IntcodeError> {
+        let mut events: Vec<TraceEvent> = Vec::new();
+        let status = self.machine.step_traced(&mut events)?;
+        let watched = self.changed_watchpoint();
+
+        let stop = match status {
+            Status::Halted          => Some(Stop::Halted),
+            Status::WaitingForInput => Some(Stop::WaitingForInput),
+            _                       => watched,
+        };
+        Ok((events.pop(), stop))
+    }
+
+    // Runs until a breakpoint or watchpoint trips, the machine halts or needs
+    // input, or (if given) the step limit is reached. Every instruction
+    // executed is passed to on_event, e.g. to show outputs as they happen.
+    //
+    // A breakpoint on the instruction we start at is ignored, so that
+    // continuing from a breakpoint actually goes somewhere
+    pub fn resume(
+        &mut self,
+        limit: Option<usize>,
+        on_event: &mut dyn FnMut(&TraceEvent),
+    ) -> Result<Stop, IntcodeError> {
+        let mut steps = 0;
+        loop {
+            if steps > 0 {
+                if let Some(stop) = self.breakpoint_at_ip() {
+                    return Ok(stop)
+                }
+            }
+            if limit == Some(steps) {
+                return Ok(Stop::Steps)
+            }
+            let (event, stop) = self.step()?;
+            steps += 1;
+            if let Some(x) = event {
+                on_event(&x);
+            }
+            if let Some(x) = stop {
+                return Ok(x)
+            }
+        }
+    }
+}
@@ -0,0 +1,270 @@
+// Instructions decoded from memory into a fixed-size form, so that executing
+// one doesn't mean picking apart the raw value and allocating for its
+// arguments every time. A machine keeps a cache of decoded instructions by
+// address, and throws away any entry the program writes over
+use super::instruction_set::InstructionSet;
+use super::{get_mode, to_address};
+use super::{IntcodeError, Memory, ParameterMode, Word};
+
+// The most arguments any instruction takes
+pub const MAX_ARGS: usize = 3;
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub struct Instruction {
+    // Raw value of the instruction, including mode digits
+    pub raw: Word,
+    pub num_args: usize,
+    // Which argument, if any, is an address to write to
+    pub dest: Option<usize>,
+    // Mode and raw value of each argument. Only the first num_args are used
+    pub modes: [ParameterMode; MAX_ARGS],
+    pub operands: [Word; MAX_ARGS],
+}
+
+#[test]
+fn test_decode() {
+    let memory = Memory::from(&[1002,4,3,4,33,21107,1,-2,0,99][..]);
+    assert_eq!(
+        Instruction::decode(&memory, 0),
+        Ok(Instruction {
+            raw: 1002,
+            num_args: 3,
+            dest: Some(2),
+            modes: [ParameterMode::Position, ParameterMode::Immediate, ParameterMode::Position],
+            operands: [4, 3, 4],
+        })
+    );
+    assert_eq!(Instruction::decode(&memory, 5).unwrap().modes[2], ParameterMode::Relative);
+    assert_eq!(Instruction::decode(&memory, 0).unwrap().reads(0, 0), Ok(vec![4]));
+    let relative = Instruction::decode(&Memory::from(&[2201,-1,1,0][..]), 0).unwrap();
+    assert_eq!(relative.reads(0, 3), Ok(vec![2, 4]));
+    assert_eq!(relative.reads(0, 0), Err(IntcodeError::NegativeAddress { ip: 0, instruction: 2201, address: -1 }));
+    assert_eq!(Instruction::decode(&memory, 9).unwrap().size(), 1);
+
+    let memory = Memory::from(&[11101,1,2,3,303,0,42][..]);
+    assert_eq!(
+        Instruction::decode(&memory, 0),
+        Err(IntcodeError::WriteToImmediate { ip: 0, instruction: 11101 })
+    );
+    assert_eq!(
+        Instruction::decode(&memory, 4),
+        Err(IntcodeError::InvalidMode { ip: 4, instruction: 303, mode: 3 })
+    );
+    assert_eq!(
+        Instruction::decode(&memory, 6),
+        Err(IntcodeError::InvalidOpcode { ip: 6, instruction: 42 })
+    );
+
+    // Only what the given set defines is an instruction
+    let memory = Memory::from(&[1,0,0,0,2,0,0,0][..]);
+    let set = InstructionSet::only(&[1, 99]);
+    assert_eq!(Instruction::decode_with(&memory, 0, &set).map(|x| x.size()), Ok(4));
+    assert_eq!(
+        Instruction::decode_with(&memory, 4, &set),
+        Err(IntcodeError::InvalidOpcode { ip: 4, instruction: 2 })
+    );
+}
+impl Instruction {
+    // Decodes the instruction at ip as one of the standard instructions,
+    // without executing it or recording any address as touched
+    pub fn decode(memory: &Memory, ip: usize) -> Result<Instruction, IntcodeError> {
+        Instruction::decode_with(memory, ip, InstructionSet::builtin())
+    }
+
+    // As decode(), for an instruction from the given set
+    pub fn decode_with(memory: &Memory, ip: usize, set: &InstructionSet) -> Result<Instruction, IntcodeError> {
+        let raw = memory.get(ip);
+        let definition = set.get(raw).ok_or(IntcodeError::InvalidOpcode { ip, instruction: raw })?;
+        let mut instruction = Instruction::new(raw, ip, definition.num_args, definition.dest)?;
+        for i in 0..instruction.num_args {
+            instruction.operands[i] = memory.get(ip + i + 1);
+        }
+        Ok(instruction)
+    }
+
+    // An instruction taking num_args arguments, with its parameter modes
+    // picked out of raw but its operands all left as 0, for anything which
+    // reads operands its own way
+    pub fn new(raw: Word, ip: usize, num_args: usize, dest: Option<usize>) -> Result<Instruction, IntcodeError> {
+        let mut instruction = Instruction {
+            raw,
+            num_args,
+            dest,
+            modes: [ParameterMode::Position; MAX_ARGS],
+            operands: [0; MAX_ARGS],
+        };
+
+        let mut mode_digits = raw / 100;
+        for i in 0..num_args {
+            instruction.modes[i] = match get_mode(mode_digits % 10) {
+                Some(x) => x,
+                None    => {
+                    return Err(IntcodeError::InvalidMode { ip, instruction: raw, mode: mode_digits % 10 })
+                },
+            };
+            mode_digits /= 10;
+        }
+        if instruction.dest_mode() == Some(ParameterMode::Immediate) {
+            return Err(IntcodeError::WriteToImmediate { ip, instruction: raw })
+        }
+        Ok(instruction)
+    }
+
+    // Number of memory cells the instruction takes up
+    pub fn size(&self) -> usize {
+        self.num_args + 1
+    }
+
+    fn dest_mode(&self) -> Option<ParameterMode> {
+        self.dest.map(|x| self.modes[x])
+    }
+
+    // The address this instruction writes to, if it writes at all
+    pub fn dest(&self, ip: usize, relative_base: Word) -> Result<Option<usize>, IntcodeError> {
+        let dest = match self.dest {
+            Some(x) => x,
+            None    => { return Ok(None) },
+        };
+        let raw = self.operands[dest];
+        let address = match self.modes[dest] {
+            ParameterMode::Relative => {
+                relative_base.checked_add(raw)
+                    .ok_or(IntcodeError::Overflow { ip, instruction: self.raw })?
+            },
+            _                       => raw,
+        };
+        to_address(address, ip, self.raw).map(Some)
+    }
+
+    // Addresses the instruction reads its arguments from. Immediate arguments
+    // and the write destination aren't reads
+    pub fn reads(&self, ip: usize, relative_base: Word) -> Result<Vec<usize>, IntcodeError> {
+        let mut reads = Vec::new();
+        for i in 0..self.num_args {
+            if self.dest == Some(i) {
+                continue
+            }
+            let address = match self.modes[i] {
+                ParameterMode::Immediate => continue,
+                ParameterMode::Position  => self.operands[i],
+                ParameterMode::Relative  => {
+                    relative_base.checked_add(self.operands[i])
+                        .ok_or(IntcodeError::Overflow { ip, instruction: self.raw })?
+                },
+            };
+            reads.push(to_address(address, ip, self.raw)?);
+        }
+        Ok(reads)
+    }
+
+    // Resolves each argument according to its mode, reading memory where
+    // needed. A write destination is given as the address to write to rather
+    // than read from
+    pub fn args(&self, memory: &mut Memory, ip: usize, relative_base: Word) -> Result<[Word; MAX_ARGS], IntcodeError> {
+        let overflow = IntcodeError::Overflow { ip, instruction: self.raw };
+        let mut args = [0; MAX_ARGS];
+
+        for (i, arg) in args.iter_mut().enumerate().take(self.num_args) {
+            let raw_arg = self.operands[i];
+            let address = match self.modes[i] {
+                ParameterMode::Immediate => {
+                    *arg = raw_arg;
+                    continue
+                },
+                ParameterMode::Position  => raw_arg,
+                ParameterMode::Relative  => relative_base.checked_add(raw_arg).ok_or(overflow.clone())?,
+            };
+            let address = to_address(address, ip, self.raw)?;
+            *arg = match self.dest == Some(i) {
+                true  => address as Word,
+                false => memory.read(address),
+            };
+        }
+        Ok(args)
+    }
+}
+
+#[test]
+fn test_args() {
+    let program = &mut Memory::from(&[1002,4,3,4,33][..]);
+    let decoded = Instruction::decode(program, 0).unwrap();
+    assert_eq!(decoded.args(program, 0, 0), Ok([33,3,4]));
+
+    // Relative reads are offset from the relative base, as are relative
+    // destinations
+    let program = &mut Memory::from(&[22201,1,2,3,7,11][..]);
+    let decoded = Instruction::decode(program, 0).unwrap();
+    assert_eq!(decoded.args(program, 0, 3), Ok([7,11,6]));
+    assert_eq!(
+        decoded.args(program, 0, -4),
+        Err(IntcodeError::NegativeAddress { ip: 0, instruction: 22201, address: -3 })
+    );
+}
+
+#[test]
+fn test_cache() {
+    let mut memory = Memory::from(&[1002,4,3,4,33,99][..]);
+    let mut cache = DecodeCache::default();
+    assert_eq!(cache.get(&memory, 0).unwrap().operands, [4, 3, 4]);
+
+    // Changes to memory aren't noticed until the cache is told about them
+    memory.set(2, 5);
+    assert_eq!(cache.get(&memory, 0).unwrap().operands, [4, 3, 4]);
+    cache.invalidate(2);
+    assert_eq!(cache.get(&memory, 0).unwrap().operands, [4, 5, 4]);
+
+    // Writes just past the end of an instruction leave it alone
+    cache.get(&memory, 5).unwrap();
+    cache.invalidate(4);
+    assert_eq!(cache.entries.iter().filter(|x| x.is_some()).count(), 2);
+}
+// Decoded instructions by address
+#[derive(Debug)]
+#[derive(Clone, Default)]
+pub struct DecodeCache {
+    entries: Vec<Option<Instruction>>,
+}
+
+impl DecodeCache {
+    // Decodes the instruction at ip, reusing an earlier decode if there is one.
+    // Only instructions in the contiguous part of memory are cached
+    pub fn get(&mut self, memory: &Memory, ip: usize) -> Result<Instruction, IntcodeError> {
+        self.get_with(memory, ip, InstructionSet::builtin())
+    }
+
+    // As get(), for instructions from the given set. A cache must only ever be
+    // used with one set, or cleared in between
+    pub fn get_with(&mut self, memory: &Memory, ip: usize, set: &InstructionSet) -> Result<Instruction, IntcodeError> {
+        if let Some(Some(x)) = self.entries.get(ip) {
+            return Ok(*x)
+        }
+        let instruction = Instruction::decode_with(memory, ip, set)?;
+        if ip < memory.as_slice().len() {
+            if self.entries.len() <= ip {
+                self.entries.resize(memory.as_slice().len(), None);
+            }
+            self.entries[ip] = Some(instruction);
+        }
+        Ok(instruction)
+    }
+
+    // Forgets any decoded instruction which the given address is part of.
+    // Must be called whenever memory is written to
+    pub fn invalidate(&mut self, address: usize) {
+        let start = address.saturating_sub(MAX_ARGS);
+        let end = std::cmp::min(address + 1, self.entries.len());
+        for ip in start..end {
+            if let Some(x) = self.entries[ip] {
+                if ip + x.size() > address {
+                    self.entries[ip] = None;
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
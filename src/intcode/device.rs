@@ -0,0 +1,170 @@
+// Devices which an Intcode machine reads input from (opcode 3) and writes
+// output to (opcode 4). By default a machine uses its own Queue, but it can be
+// run against any Device, e.g. to talk to a terminal or another program
+use std::collections::VecDeque;
+use std::io;
+use std::io::{BufRead, Write};
+
+use super::Word;
+
+pub trait Device {
+    // The next value of input, or None if there isn't one (yet). A machine
+    // which gets None waits for input rather than failing
+    fn read(&mut self) -> Option<Word>;
+    fn write(&mut self, value: Word);
+}
+
+// Input given up front, consumed in order, and every output collected
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Default)]
+pub struct Queue {
+    pub input: VecDeque<Word>,
+    pub output: Vec<Word>,
+}
+
+impl Queue {
+    pub fn new(input: &[Word]) -> Queue {
+        Queue {
+            input: input.iter().copied().collect(),
+            output: Vec::new(),
+        }
+    }
+}
+
+impl Device for Queue {
+    fn read(&mut self) -> Option<Word> {
+        self.input.pop_front()
+    }
+
+    fn write(&mut self, value: Word) {
+        self.output.push(value);
+    }
+}
+
+// Reads input from stdin and writes output to stdout.
+//
+// In numeric mode, each line of stdin is a single value and each output is
+// printed on a line of its own. In ASCII mode, input is sent a character at a
+// time (lines ending in '\n') and output is printed as text, except for values
+// outside the ASCII range, which are printed as numbers on a line of their own.
+// End of input, or a line that isn't a number, reads as no input
+pub struct Stdio {
+    ascii: bool,
+    pending: VecDeque<Word>,
+}
+
+impl Stdio {
+    pub fn numeric() -> Stdio {
+        Stdio { ascii: false, pending: VecDeque::new() }
+    }
+
+    pub fn ascii() -> Stdio {
+        Stdio { ascii: true, pending: VecDeque::new() }
+    }
+}
+
+impl Device for Stdio {
+    fn read(&mut self) -> Option<Word> {
+        if self.pending.is_empty() {
+            io::stdout().flush().ok();
+            let mut line = String::new();
+            if io::stdin().lock().read_line(&mut line).ok()? == 0 {
+                return None
+            }
+            if self.ascii {
+                let line = line.trim_end_matches(&['\r', '\n'][..]);
+                self.pending.extend(line.bytes().map(Word::from));
+                self.pending.push_back(Word::from(b'\n'));
+            } else {
+                self.pending.push_back(line.trim().parse().ok()?);
+            }
+        }
+        self.pending.pop_front()
+    }
+
+    fn write(&mut self, value: Word) {
+        match value {
+            0..=127 if self.ascii => print!("{}", value as u8 as char),
+            _                     => println!("{}", value),
+        }
+    }
+}
+
+#[test]
+fn test_recorder() {
+    let mut recorder = Recorder::new(Queue::new(&[3, 4]));
+    let mut machine = super::Machine::new(&[3,11,3,12,2,11,12,13,4,13,99,0,0,0]);
+    assert_eq!(machine.run_with(&mut recorder), Ok(super::Status::Halted));
+    assert_eq!(recorder.inputs, vec![3, 4]);
+    assert_eq!(recorder.outputs, vec![12]);
+    assert_eq!(recorder.into_inner().output, vec![12]);
+    assert!(machine.output().is_empty());
+}
+// Passes everything through to another device, keeping a copy of every value
+// read and written along the way
+pub struct Recorder<D: Device> {
+    device: D,
+    pub inputs: Vec<Word>,
+    pub outputs: Vec<Word>,
+}
+
+impl<D: Device> Recorder<D> {
+    pub fn new(device: D) -> Recorder<D> {
+        Recorder { device, inputs: Vec::new(), outputs: Vec::new() }
+    }
+
+    pub fn into_inner(self) -> D {
+        self.device
+    }
+}
+
+impl<D: Device> Device for Recorder<D> {
+    fn read(&mut self) -> Option<Word> {
+        let value = self.device.read();
+        self.inputs.extend(value);
+        value
+    }
+
+    fn write(&mut self, value: Word) {
+        self.outputs.push(value);
+        self.device.write(value);
+    }
+}
+
+#[test]
+fn test_from_fn() {
+    let mut input = vec![0, 21, 4];
+    let mut output = Vec::new();
+    let mut machine = super::Machine::new(&super::fixtures::ECHO);
+    let status = machine.run_with(&mut from_fn(|| input.pop(), |x| output.push(x)));
+    assert_eq!(status, Ok(super::Status::Halted));
+    assert_eq!(output, vec![8, 42]);
+}
+// A device made from a pair of closures
+pub struct FnDevice<R, W> {
+    read: R,
+    write: W,
+}
+
+pub fn from_fn<R, W>(read: R, write: W) -> FnDevice<R, W>
+    where
+        R: FnMut() -> Option<Word>,
+        W: FnMut(Word),
+{
+    FnDevice { read, write }
+}
+
+impl<R, W> Device for FnDevice<R, W>
+    where
+        R: FnMut() -> Option<Word>,
+        W: FnMut(Word),
+{
+    fn read(&mut self) -> Option<Word> {
+        (self.read)()
+    }
+
+    fn write(&mut self, value: Word) {
+        (self.write)(value)
+    }
+}
@@ -0,0 +1,171 @@
+use std::fmt;
+
+use super::{get_modes, get_opcode, num_args, writes_to_program};
+use super::{Opcode, ParameterMode, Word};
+
+// A single argument to an instruction, as it appears on the tape
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub struct Operand {
+    pub mode: ParameterMode,
+    pub value: Word,
+    // Whether the instruction writes to this operand rather than reading it
+    pub is_dest: bool,
+}
+
+#[test]
+fn test_display_operand() {
+    let operand = |mode, value, is_dest| format!("{}", Operand { mode, value, is_dest });
+    assert_eq!(operand(ParameterMode::Position, 225, false), "[225]");
+    assert_eq!(operand(ParameterMode::Immediate, -6, false), "#-6");
+    assert_eq!(operand(ParameterMode::Relative, 3, false), "rb+3");
+    assert_eq!(operand(ParameterMode::Relative, -3, false), "rb-3");
+    assert_eq!(operand(ParameterMode::Position, 225, true), "->225");
+    assert_eq!(operand(ParameterMode::Relative, -1, true), "->rb-1");
+}
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_dest {
+            write!(f, "->")?;
+        }
+        match self.mode {
+            ParameterMode::Position if self.is_dest => write!(f, "{}", self.value),
+            ParameterMode::Position  => write!(f, "[{}]", self.value),
+            ParameterMode::Immediate => write!(f, "#{}", self.value),
+            ParameterMode::Relative if self.value < 0 => write!(f, "rb{}", self.value),
+            ParameterMode::Relative  => write!(f, "rb+{}", self.value),
+        }
+    }
+}
+
+// One line of a disassembly listing: either an instruction along with its
+// operands, or a single value that doesn't decode as an instruction
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum Line {
+    Instruction { address: usize, opcode: Opcode, operands: Vec<Operand> },
+    Data { address: usize, value: Word },
+}
+
+impl Line {
+    pub fn address(&self) -> usize {
+        match self {
+            Line::Instruction { address, .. } => *address,
+            Line::Data { address, .. }        => *address,
+        }
+    }
+
+    // Number of values on the tape covered by this line
+    pub fn size(&self) -> usize {
+        match self {
+            Line::Instruction { operands, .. } => operands.len() + 1,
+            Line::Data { .. }                  => 1,
+        }
+    }
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}: ", self.address())?;
+        match self {
+            Line::Instruction { opcode, operands, .. } => {
+                write!(f, "{}", opcode.mnemonic())?;
+                for (i, operand) in operands.iter().enumerate() {
+                    let separator = if i == 0 { " " } else { ", " };
+                    write!(f, "{}{}", separator, operand)?;
+                }
+                Ok(())
+            },
+            Line::Data { value, .. } => write!(f, ".data {}", value),
+        }
+    }
+}
+
+#[test]
+fn test_decode() {
+    let tape = [1002,4,3,4,33];
+    assert_eq!(
+        decode(&tape, 0),
+        Some(Line::Instruction {
+            address: 0,
+            opcode: Opcode::Mul,
+            operands: vec![
+                Operand { mode: ParameterMode::Position, value: 4, is_dest: false },
+                Operand { mode: ParameterMode::Immediate, value: 3, is_dest: false },
+                Operand { mode: ParameterMode::Position, value: 4, is_dest: true },
+            ],
+        })
+    );
+    // Not an opcode
+    assert_eq!(decode(&tape, 4), None);
+    // Runs off the end of the tape
+    assert_eq!(decode(&[1,0,0], 0), None);
+    // Invalid mode, and writing to an immediate operand
+    assert_eq!(decode(&[301,0,0,0], 0), None);
+    assert_eq!(decode(&[11101,0,0,0], 0), None);
+}
+// Attempts to decode the instruction at the given address, returning None if
+// the value there can't be the start of a valid instruction
+pub fn decode(tape: &[Word], address: usize) -> Option<Line> {
+    let raw_value = *tape.get(address)?;
+    let opcode = get_opcode(&raw_value)?;
+    let num_args = num_args(&opcode);
+    let modes = get_modes(raw_value, num_args).ok()?;
+
+    if address + num_args >= tape.len() {
+        return None
+    }
+    let mut operands = Vec::with_capacity(num_args);
+    for (i, mode) in modes.into_iter().enumerate() {
+        let is_dest = i + 1 == num_args && writes_to_program(&opcode);
+        if is_dest && mode == ParameterMode::Immediate {
+            return None
+        }
+        operands.push(Operand { mode, value: tape[address + i + 1], is_dest });
+    }
+    Some(Line::Instruction { address, opcode, operands })
+}
+
+#[test]
+fn test_disassemble() {
+    let tape = [1002,4,3,4,33,104,-1,99,7];
+    let lines: Vec<String> = disassemble(&tape).iter().map(|x| x.to_string()).collect();
+    assert_eq!(
+        lines,
+        vec![
+            "0000: MUL [4], #3, ->4",
+            "0004: .data 33",
+            "0005: OUT #-1",
+            "0007: STOP",
+            "0008: .data 7",
+        ]
+    );
+}
+// Disassembles a whole tape with a linear sweep from address 0.
+// Anything that doesn't decode as an instruction is shown as data, and
+// decoding carries on from the next address
+pub fn disassemble(tape: &[Word]) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut address = 0;
+
+    while address < tape.len() {
+        let line = match decode(tape, address) {
+            Some(x) => x,
+            None    => Line::Data { address, value: tape[address] },
+        };
+        address += line.size();
+        lines.push(line);
+    }
+    lines
+}
+
+// A printable listing of the whole tape, one line per instruction
+pub fn listing(tape: &[Word]) -> String {
+    disassemble(tape)
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
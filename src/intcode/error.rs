@@ -0,0 +1,104 @@
+use std::error::Error;
+use std::fmt;
+
+use super::Word;
+
+// Everything that can go wrong while executing an Intcode program.
+// Each error carries the instruction pointer and raw value of the instruction
+// which caused it, so a harness can report exactly where the program died
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum IntcodeError {
+    InvalidOpcode { ip: usize, instruction: Word },
+    InvalidMode { ip: usize, instruction: Word, mode: Word },
+    // An address too large for a usize, which can only happen on targets
+    // where a usize is narrower than a Word
+    OutOfBounds { ip: usize, instruction: Word, address: Word },
+    NegativeAddress { ip: usize, instruction: Word, address: Word },
+    InputExhausted { ip: usize, instruction: Word },
+    WriteToImmediate { ip: usize, instruction: Word },
+    Overflow { ip: usize, instruction: Word },
+    // Ran for the maximum number of steps allowed without halting or needing
+    // input. The instruction given is the next one which would have run
+    StepLimit { ip: usize, instruction: Word, steps: usize },
+    // Came back to a state it had already been in, without any input or
+    // output in between, so it will never stop. length is the number of
+    // instructions in the cycle
+    InfiniteLoop { ip: usize, instruction: Word, length: usize },
+}
+
+impl IntcodeError {
+    pub fn ip(&self) -> usize {
+        match self {
+            IntcodeError::InvalidOpcode { ip, .. }    => *ip,
+            IntcodeError::InvalidMode { ip, .. }      => *ip,
+            IntcodeError::OutOfBounds { ip, .. }      => *ip,
+            IntcodeError::NegativeAddress { ip, .. }  => *ip,
+            IntcodeError::InputExhausted { ip, .. }   => *ip,
+            IntcodeError::WriteToImmediate { ip, .. } => *ip,
+            IntcodeError::Overflow { ip, .. }         => *ip,
+            IntcodeError::StepLimit { ip, .. }        => *ip,
+            IntcodeError::InfiniteLoop { ip, .. }     => *ip,
+        }
+    }
+
+    pub fn instruction(&self) -> Word {
+        match self {
+            IntcodeError::InvalidOpcode { instruction, .. }    => *instruction,
+            IntcodeError::InvalidMode { instruction, .. }      => *instruction,
+            IntcodeError::OutOfBounds { instruction, .. }      => *instruction,
+            IntcodeError::NegativeAddress { instruction, .. }  => *instruction,
+            IntcodeError::InputExhausted { instruction, .. }   => *instruction,
+            IntcodeError::WriteToImmediate { instruction, .. } => *instruction,
+            IntcodeError::Overflow { instruction, .. }         => *instruction,
+            IntcodeError::StepLimit { instruction, .. }        => *instruction,
+            IntcodeError::InfiniteLoop { instruction, .. }     => *instruction,
+        }
+    }
+}
+
+#[test]
+fn test_display() {
+    let err = IntcodeError::InvalidMode { ip: 12, instruction: 30001, mode: 3 };
+    assert_eq!(
+        format!("{}", err),
+        "invalid parameter mode 3 (instruction 30001 at ip 12)"
+    );
+}
+impl fmt::Display for IntcodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IntcodeError::InvalidOpcode { .. } => {
+                write!(f, "invalid opcode")?
+            },
+            IntcodeError::InvalidMode { mode, .. } => {
+                write!(f, "invalid parameter mode {}", mode)?
+            },
+            IntcodeError::OutOfBounds { address, .. } => {
+                write!(f, "address {} is out of bounds", address)?
+            },
+            IntcodeError::NegativeAddress { address, .. } => {
+                write!(f, "negative address {}", address)?
+            },
+            IntcodeError::InputExhausted { .. } => {
+                write!(f, "no input left to read")?
+            },
+            IntcodeError::WriteToImmediate { .. } => {
+                write!(f, "cannot write to an immediate operand")?
+            },
+            IntcodeError::Overflow { .. } => {
+                write!(f, "arithmetic overflow")?
+            },
+            IntcodeError::StepLimit { steps, .. } => {
+                write!(f, "step limit of {} reached", steps)?
+            },
+            IntcodeError::InfiniteLoop { length, .. } => {
+                write!(f, "infinite loop of {} instructions", length)?
+            },
+        }
+        write!(f, " (instruction {} at ip {})", self.instruction(), self.ip())
+    }
+}
+
+impl Error for IntcodeError {}
@@ -0,0 +1,379 @@
+// Table-driven instruction sets, for running machines on something other
+// than the standard Intcode instructions: a restricted subset (e.g. day 2's
+// ADD, MUL and STOP only, so that anything else fails loudly), or
+// experimental opcodes of our own.
+//
+// Each opcode is registered with its arity, which argument (if any) is an
+// address to write to, and a closure which executes it against a Cpu. The
+// VM decodes and resolves parameter modes before calling the closure, giving
+// write destinations as the address to write to, as Instruction::args()
+// does.
+//
+// The standard instructions are themselves an instruction set, which every
+// machine without one of its own runs on. Tracing reports each instruction
+// by its definition's mnemonic, but tools which work on tapes (disasm,
+// analysis, transpile) only know about the standard ones
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+
+use super::decode::{DecodeCache, MAX_ARGS};
+use super::device::Device;
+use super::trace::{TraceEvent, TraceSink};
+use super::{to_address, OPCODES};
+use super::{IntcodeError, Machine, Memory, Opcode, Status, Word};
+
+// Runs an instruction, given its resolved arguments. Returning
+// WaitingForInput means the instruction must be retried once there's input,
+// so it shouldn't have changed anything. Halted leaves ip where it is
+pub type Execute = dyn Fn(&mut Cpu, &[Word]) -> Result<Status, IntcodeError> + Send + Sync;
+
+#[derive(Clone)]
+pub struct Definition {
+    // The last two digits of an instruction using this opcode
+    pub value: Word,
+    pub mnemonic: String,
+    pub num_args: usize,
+    // Which argument, if any, is an address to write to
+    pub dest: Option<usize>,
+    // The standard opcode this behaves as, if it is one of the standard
+    // instructions. Traces report it, for tools which only understand those
+    pub opcode: Option<Opcode>,
+    pub execute: Arc<Execute>,
+}
+
+// What an executing instruction can see and do
+pub struct Cpu<'a> {
+    memory: &'a mut Memory,
+    decoded: &'a mut DecodeCache,
+    device: &'a mut dyn Device,
+    ip: usize,
+    instruction: Word,
+    relative_base: Word,
+    jump: Option<usize>,
+    input: Option<Word>,
+    output: Option<Word>,
+    // Only kept when tracing
+    writes: Option<Vec<(usize, Word)>>,
+}
+
+impl<'a> Cpu<'a> {
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    // Raw value of the instruction, including mode digits
+    pub fn instruction(&self) -> Word {
+        self.instruction
+    }
+
+    pub fn relative_base(&self) -> Word {
+        self.relative_base
+    }
+
+    pub fn read(&mut self, address: usize) -> Word {
+        self.memory.read(address)
+    }
+
+    pub fn write(&mut self, address: usize, value: Word) {
+        self.memory.write(address, value);
+        // The address may be part of an instruction we've already decoded
+        self.decoded.invalidate(address);
+        if let Some(writes) = &mut self.writes {
+            writes.push((address, value));
+        }
+    }
+
+    // The next value of input, or None if there isn't any yet
+    pub fn input(&mut self) -> Option<Word> {
+        self.input = self.device.read();
+        self.input
+    }
+
+    pub fn output(&mut self, value: Word) {
+        self.device.write(value);
+        self.output = Some(value);
+    }
+
+    // Carries on from target, rather than the next instruction
+    pub fn jump(&mut self, target: Word) -> Result<(), IntcodeError> {
+        self.jump = Some(to_address(target, self.ip, self.instruction)?);
+        Ok(())
+    }
+
+    pub fn adjust_relative_base(&mut self, offset: Word) -> Result<(), IntcodeError> {
+        self.relative_base = self.relative_base.checked_add(offset).ok_or_else(|| self.overflow())?;
+        Ok(())
+    }
+
+    // The error for arithmetic which overflows a Word
+    pub fn overflow(&self) -> IntcodeError {
+        IntcodeError::Overflow { ip: self.ip, instruction: self.instruction }
+    }
+}
+
+// How each of the standard opcodes runs
+fn standard(opcode: Opcode) -> Arc<Execute> {
+    match opcode {
+        Opcode::Stop => Arc::new(|_, _| Ok(Status::Halted)),
+        Opcode::Add  => Arc::new(|cpu, args| {
+            let value = args[0].checked_add(args[1]).ok_or_else(|| cpu.overflow())?;
+            cpu.write(args[2] as usize, value);
+            Ok(Status::Running)
+        }),
+        Opcode::Mul  => Arc::new(|cpu, args| {
+            let value = args[0].checked_mul(args[1]).ok_or_else(|| cpu.overflow())?;
+            cpu.write(args[2] as usize, value);
+            Ok(Status::Running)
+        }),
+        Opcode::Mov  => Arc::new(|cpu, args| {
+            match cpu.input() {
+                Some(x) => cpu.write(args[0] as usize, x),
+                None    => { return Ok(Status::WaitingForInput) },
+            }
+            Ok(Status::Running)
+        }),
+        Opcode::Out  => Arc::new(|cpu, args| {
+            cpu.output(args[0]);
+            Ok(Status::Output(args[0]))
+        }),
+        Opcode::Jnz  => Arc::new(|cpu, args| {
+            if args[0] != 0 {
+                cpu.jump(args[1])?;
+            }
+            Ok(Status::Running)
+        }),
+        Opcode::Jz   => Arc::new(|cpu, args| {
+            if args[0] == 0 {
+                cpu.jump(args[1])?;
+            }
+            Ok(Status::Running)
+        }),
+        Opcode::Lt   => Arc::new(|cpu, args| {
+            cpu.write(args[2] as usize, Word::from(args[0] < args[1]));
+            Ok(Status::Running)
+        }),
+        Opcode::Eq   => Arc::new(|cpu, args| {
+            cpu.write(args[2] as usize, Word::from(args[0] == args[1]));
+            Ok(Status::Running)
+        }),
+        Opcode::AdjustRelativeBase => Arc::new(|cpu, args| {
+            cpu.adjust_relative_base(args[0])?;
+            Ok(Status::Running)
+        }),
+    }
+}
+
+// Definitions are indexed by value, so that finding one is as cheap as the
+// match it replaces
+#[derive(Clone, Default)]
+pub struct InstructionSet {
+    definitions: Vec<Option<Definition>>,
+}
+
+impl fmt::Debug for InstructionSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map()
+            .entries(self.definitions.iter().flatten().map(|x| (x.value, &x.mnemonic)))
+            .finish()
+    }
+}
+
+impl InstructionSet {
+    // An instruction set with no instructions at all
+    pub fn new() -> InstructionSet {
+        InstructionSet::default()
+    }
+
+    // Every standard instruction, behaving exactly as it does by default
+    pub fn standard() -> InstructionSet {
+        let mut set = InstructionSet::new();
+        for info in OPCODES.iter() {
+            set.insert(Definition {
+                value: info.value,
+                mnemonic: info.mnemonic.to_string(),
+                num_args: info.num_args,
+                dest: info.dest(),
+                opcode: Some(info.opcode),
+                execute: standard(info.opcode),
+            });
+        }
+        set
+    }
+
+    // The standard instructions, shared by every machine which hasn't been
+    // given a set of its own
+    pub fn builtin() -> &'static InstructionSet {
+        static BUILTIN: OnceLock<InstructionSet> = OnceLock::new();
+        BUILTIN.get_or_init(InstructionSet::standard)
+    }
+
+    // Only the given standard instructions, e.g. only(&[1, 2, 99]) for day 2
+    pub fn only(values: &[Word]) -> InstructionSet {
+        let mut set = InstructionSet::standard();
+        for definition in set.definitions.iter_mut() {
+            if definition.as_ref().is_some_and(|x| !values.contains(&x.value)) {
+                *definition = None;
+            }
+        }
+        set
+    }
+
+    // Adds an instruction, replacing any other with the same value
+    pub fn insert(&mut self, definition: Definition) {
+        assert!((0..100).contains(&definition.value), "Opcodes must be two digits");
+        assert!(definition.num_args <= MAX_ARGS, "Instructions take at most {} arguments", MAX_ARGS);
+        assert!(definition.dest.map_or(true, |x| x < definition.num_args), "Destination must be an argument");
+        if self.definitions.is_empty() {
+            self.definitions.resize(100, None);
+        }
+        let value = definition.value as usize;
+        self.definitions[value] = Some(definition);
+    }
+
+    pub fn register<F>(&mut self, value: Word, mnemonic: &str, num_args: usize, dest: Option<usize>, execute: F)
+        where
+            F: Fn(&mut Cpu, &[Word]) -> Result<Status, IntcodeError> + Send + Sync + 'static,
+    {
+        self.insert(Definition {
+            value,
+            mnemonic: mnemonic.to_string(),
+            num_args,
+            dest,
+            opcode: None,
+            execute: Arc::new(execute),
+        });
+    }
+
+    pub fn remove(&mut self, value: Word) -> Option<Definition> {
+        let value = usize::try_from(value).ok()?;
+        self.definitions.get_mut(value)?.take()
+    }
+
+    // The definition for an instruction's opcode, given its raw value
+    pub fn get(&self, instruction: Word) -> Option<&Definition> {
+        let value = usize::try_from(instruction % 100).ok()?;
+        self.definitions.get(value)?.as_ref()
+    }
+}
+
+#[test]
+fn test_only() {
+    let day2: Vec<Word> = crate::input_to_vec_t_fromstr(include_str!("../bin/day2/input"), ',');
+    let mut machine = Machine::new(&day2);
+    machine.set_instruction_set(Arc::new(InstructionSet::only(&[1, 2, 99])));
+    machine.patch(1, 12);
+    machine.patch(2, 2);
+    assert_eq!(machine.run(), Ok(Status::Halted));
+    assert_eq!(machine.memory().get(0), 3085697);
+
+    // Day 5 starts with input, which day 2 doesn't have
+    let day5: Vec<Word> = crate::input_to_vec_t_fromstr(include_str!("../bin/day5/input"), ',');
+    let mut machine = Machine::new(&day5);
+    machine.set_instruction_set(Arc::new(InstructionSet::only(&[1, 2, 99])));
+    assert_eq!(machine.run(), Err(IntcodeError::InvalidOpcode { ip: 0, instruction: 3 }));
+
+    // With everything, it runs just as it would by default
+    let mut machine = Machine::new(&day5);
+    machine.set_instruction_set(Arc::new(InstructionSet::standard()));
+    machine.push_input(5);
+    assert_eq!(machine.run(), Ok(Status::Halted));
+    assert_eq!(machine.output(), &[9571668]);
+}
+
+#[test]
+fn test_register() {
+    let mut set = InstructionSet::standard();
+    // NEG a, ->b
+    set.register(10, "NEG", 2, Some(1), |cpu, args| {
+        let value = args[0].checked_neg().ok_or_else(|| cpu.overflow())?;
+        cpu.write(args[1] as usize, value);
+        Ok(Status::Running)
+    });
+    let set = Arc::new(set);
+
+    let tape = [110,7,7,4,7,99,0,0];
+    let mut machine = Machine::new(&tape);
+    machine.set_instruction_set(set.clone());
+    let mut events: Vec<TraceEvent> = Vec::new();
+    assert_eq!(machine.run_traced(&mut events), Ok(Status::Halted));
+    assert_eq!(machine.output(), &[-7]);
+    // Every instruction is traced, under the name it was registered with
+    assert_eq!(
+        events.iter().map(|x| (x.ip, x.mnemonic.as_str(), x.opcode)).collect::<Vec<_>>(),
+        vec![(0, "NEG", None), (3, "OUT", Some(Opcode::Out)), (5, "STOP", Some(Opcode::Stop))]
+    );
+    assert_eq!(events[0].writes, vec![(7, -7)]);
+
+    let mut machine = Machine::new(&[11110,7,7]);
+    machine.set_instruction_set(set);
+    assert_eq!(machine.step(), Err(IntcodeError::WriteToImmediate { ip: 0, instruction: 11110 }));
+}
+impl Machine {
+    // Runs the machine on the given instruction set from now on, instead of
+    // the standard one
+    pub fn set_instruction_set(&mut self, set: Arc<InstructionSet>) {
+        self.instruction_set = Some(set);
+        self.decoded.clear();
+    }
+
+    pub fn instruction_set(&self) -> Option<&InstructionSet> {
+        self.instruction_set.as_deref()
+    }
+
+    // Executes one instruction from the given set
+    pub(super) fn execute_with_set(
+        &mut self,
+        set: &InstructionSet,
+        device: &mut dyn Device,
+        sink: Option<&mut dyn TraceSink>,
+    ) -> Result<Status, IntcodeError> {
+        let ip = self.ip;
+        let decoded = self.decoded.get_with(&self.memory, ip, set)?;
+        let raw = decoded.raw;
+        let definition = set.get(raw).ok_or(IntcodeError::InvalidOpcode { ip, instruction: raw })?;
+        let args = decoded.args(&mut self.memory, ip, self.relative_base)?;
+        let args = &args[..decoded.num_args];
+        let reads = match sink {
+            Some(_) => decoded.reads(ip, self.relative_base)?,
+            None    => Vec::new(),
+        };
+
+        let mut cpu = Cpu {
+            memory: &mut self.memory,
+            decoded: &mut self.decoded,
+            device,
+            ip,
+            instruction: raw,
+            relative_base: self.relative_base,
+            jump: None,
+            input: None,
+            output: None,
+            writes: sink.as_ref().map(|_| Vec::new()),
+        };
+        let status = (definition.execute)(&mut cpu, args)?;
+        if status == Status::WaitingForInput {
+            return Ok(status)
+        }
+        let Cpu { relative_base, jump, input, output, writes, .. } = cpu;
+        self.relative_base = relative_base;
+        if status != Status::Halted {
+            self.ip = jump.unwrap_or(ip + decoded.size());
+        }
+
+        if let Some(sink) = sink {
+            sink.record(&TraceEvent {
+                ip,
+                instruction: raw,
+                opcode: definition.opcode,
+                mnemonic: definition.mnemonic.clone(),
+                args: args.to_vec(),
+                reads,
+                writes: writes.unwrap_or_default(),
+                input,
+                output,
+            });
+        }
+        Ok(status)
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use super::Word;
+
+// Addresses below this are stored in a flat Vec, which grows as needed.
+// Anything above it is assumed to be the odd far-flung scratch value, and
+// goes into a sparse map instead so we don't allocate gigabytes for it
+const DENSE_LIMIT: usize = 1 << 20;
+
+// Intcode memory: any address which hasn't been written reads as zero, and
+// writes beyond the end of the initial tape grow the memory to fit.
+//
+// Memory also keeps track of every address the program has read from or
+// written to, so that we can find out afterwards which parts were touched
+#[derive(PartialEq)]
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct Memory {
+    dense: Vec<Word>,
+    sparse: HashMap<usize, Word>,
+    touched: BTreeSet<usize>,
+}
+
+impl From<&[Word]> for Memory {
+    fn from(tape: &[Word]) -> Self {
+        Memory {
+            dense: tape.to_vec(),
+            sparse: HashMap::new(),
+            touched: BTreeSet::new(),
+        }
+    }
+}
+
+#[test]
+fn test_get_set() {
+    let mut memory = Memory::from(&[1,2,3][..]);
+    assert_eq!(memory.get(1), 2);
+    assert_eq!(memory.get(100), 0);
+
+    memory.set(10, 42);
+    assert_eq!(memory.get(10), 42);
+    assert_eq!(memory.as_slice(), &[1,2,3,0,0,0,0,0,0,0,42]);
+
+    memory.set(DENSE_LIMIT * 4, 7);
+    memory.set(DENSE_LIMIT * 2, 5);
+    assert_eq!(memory.get(DENSE_LIMIT * 4), 7);
+    assert_eq!(memory.as_slice().len(), 11);
+    assert_eq!(memory.far_values(), vec![(DENSE_LIMIT * 2, 5), (DENSE_LIMIT * 4, 7)]);
+}
+
+#[test]
+fn test_touched() {
+    let mut memory = Memory::from(&[1,2,3][..]);
+    memory.get(0);
+    memory.read(2);
+    memory.read(50);
+    memory.write(DENSE_LIMIT + 1, 9);
+    assert_eq!(
+        memory.touched().iter().copied().collect::<Vec<usize>>(),
+        vec![2, 50, DENSE_LIMIT + 1]
+    );
+}
+impl Memory {
+    // Looks up a value without recording the address as touched
+    pub fn get(&self, addr: usize) -> Word {
+        match self.dense.get(addr) {
+            Some(x) => *x,
+            None    => *self.sparse.get(&addr).unwrap_or(&0),
+        }
+    }
+
+    // Stores a value without recording the address as touched
+    pub fn set(&mut self, addr: usize, value: Word) {
+        if addr < self.dense.len() {
+            self.dense[addr] = value;
+        } else if addr < DENSE_LIMIT {
+            self.dense.resize(addr + 1, 0);
+            self.dense[addr] = value;
+        } else {
+            self.sparse.insert(addr, value);
+        }
+    }
+
+    // Reads a value on behalf of the running program
+    pub fn read(&mut self, addr: usize) -> Word {
+        self.touched.insert(addr);
+        self.get(addr)
+    }
+
+    // Writes a value on behalf of the running program
+    pub fn write(&mut self, addr: usize, value: Word) {
+        self.touched.insert(addr);
+        self.set(addr, value)
+    }
+
+    // Every address the program has read from or written to
+    pub fn touched(&self) -> &BTreeSet<usize> {
+        &self.touched
+    }
+
+    // The contiguous part of memory starting from address 0: the initial
+    // tape plus anything it has grown by
+    pub fn as_slice(&self) -> &[Word] {
+        &self.dense
+    }
+
+    // Values stored too far out to be part of as_slice(), in address order
+    pub fn far_values(&self) -> Vec<(usize, Word)> {
+        let mut values: Vec<(usize, Word)> = self.sparse.iter()
+            .map(|(addr, value)| (*addr, *value))
+            .collect();
+        values.sort_unstable();
+        values
+    }
+}
+
+#[test]
+fn test_same_values() {
+    let mut memory = Memory::from(&[1, 2, 3][..]);
+    let other = memory.clone();
+    memory.read(1);
+    assert!(memory.same_values(&other));
+    memory.write(DENSE_LIMIT + 1, 4);
+    assert!(!memory.same_values(&other));
+    memory.set(DENSE_LIMIT + 1, 0);
+    memory.write(0, 1);
+    assert!(!memory.same_values(&other));
+}
+impl Memory {
+    // Whether two memories hold the same values, regardless of which
+    // addresses have been touched. Memories which are the same also hash the
+    // same, but not the other way round
+    pub fn same_values(&self, other: &Memory) -> bool {
+        self.dense == other.dense && self.far_values() == other.far_values()
+    }
+}
+
+// Two memories hash the same if they hold the same values, regardless of
+// which addresses have been touched
+impl Hash for Memory {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.dense.hash(state);
+        self.far_values().hash(state);
+    }
+}
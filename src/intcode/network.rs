@@ -0,0 +1,211 @@
+// Several Intcode machines wired together, output to input, such as day 7's
+// amplifier chains. Machines take it in turns to run until they need input,
+// and anything they output is delivered to whichever machines they are
+// connected to
+use super::{IntcodeError, Machine, Status, Word};
+
+// What the network as a whole is doing once it stops
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum NetworkStatus {
+    // Every machine has halted
+    Halted,
+    // No machine can make progress: those still running are all waiting for
+    // input that nobody is going to send them
+    Stalled,
+}
+
+pub struct Network {
+    machines: Vec<Machine>,
+    // (from, to) pairs: every output of the first machine is sent to the second
+    links: Vec<(usize, usize)>,
+    last_output: Vec<Option<Word>>,
+}
+
+#[test]
+fn test_network_run() {
+    // Doubles its input, then halts
+    let doubler = [3,9,1002,9,2,9,4,9,99,0];
+    let mut network = Network::new(vec![Machine::new(&doubler), Machine::new(&doubler)]);
+    network.connect(0, 1);
+    assert_eq!(network.run(), Ok(NetworkStatus::Stalled));
+
+    network.push_input(0, 5);
+    assert_eq!(network.run(), Ok(NetworkStatus::Halted));
+    assert_eq!(network.machines()[1].output(), &[20]);
+    assert_eq!(network.last_output(0), Some(10));
+}
+impl Network {
+    pub fn new(machines: Vec<Machine>) -> Network {
+        let last_output = vec![None; machines.len()];
+        Network { machines, links: Vec::new(), last_output }
+    }
+
+    // Builds a chain of copies of the same program, one per phase setting.
+    // Each machine receives its phase setting as its first input, and its
+    // output is connected to the input of the next. If cyclic, the last
+    // machine feeds back into the first
+    pub fn chain(tape: &[Word], phases: &[Word], cyclic: bool) -> Network {
+        let machines = phases.iter()
+            .map(|phase| {
+                let mut machine = Machine::new(tape);
+                machine.push_input(*phase);
+                machine
+            })
+            .collect();
+        let mut network = Network::new(machines);
+
+        for i in 1..phases.len() {
+            network.connect(i - 1, i);
+        }
+        if cyclic && !phases.is_empty() {
+            network.connect(phases.len() - 1, 0);
+        }
+        network
+    }
+
+    pub fn connect(&mut self, from: usize, to: usize) {
+        self.links.push((from, to));
+    }
+
+    pub fn machines(&self) -> &[Machine] {
+        &self.machines
+    }
+
+    pub fn machine_mut(&mut self, index: usize) -> &mut Machine {
+        &mut self.machines[index]
+    }
+
+    // Sends a value from outside the network to one of its machines
+    pub fn push_input(&mut self, index: usize, value: Word) {
+        self.machines[index].push_input(value);
+    }
+
+    // The most recent value output by a machine, whether or not it was
+    // delivered to another machine
+    pub fn last_output(&self, index: usize) -> Option<Word> {
+        self.last_output[index]
+    }
+
+    // Runs every machine in turn until they have all halted or none of them
+    // can make any progress.
+    //
+    // Output from a machine with outgoing connections is delivered to the
+    // machines it is connected to. Output from any other machine stays in
+    // that machine's output for the caller to collect
+    pub fn run(&mut self) -> Result<NetworkStatus, IntcodeError> {
+        loop {
+            let mut progress = false;
+
+            for i in 0..self.machines.len() {
+                let machine = &mut self.machines[i];
+                if machine.status() == Status::Halted {
+                    continue
+                }
+                let ip = machine.ip();
+                let pending = machine.pending_input().len();
+                let status = machine.run()?;
+                if status == Status::Halted || ip != machine.ip() || pending != machine.pending_input().len() {
+                    progress = true;
+                }
+
+                if let Some(x) = machine.output().last() {
+                    self.last_output[i] = Some(*x);
+                }
+                let destinations: Vec<usize> = self.links.iter()
+                    .filter(|(from, _)| *from == i)
+                    .map(|(_, to)| *to)
+                    .collect();
+                if destinations.is_empty() {
+                    continue
+                }
+                let output = self.machines[i].take_output();
+                for to in destinations {
+                    self.machines[to].extend_input(output.iter().copied());
+                }
+            }
+
+            if self.machines.iter().all(|x| x.status() == Status::Halted) {
+                return Ok(NetworkStatus::Halted)
+            }
+            if !progress {
+                return Ok(NetworkStatus::Stalled)
+            }
+        }
+    }
+}
+
+#[test]
+fn test_permutations() {
+    assert_eq!(
+        permutations(&[1,2,3]),
+        vec![vec![1,2,3], vec![1,3,2], vec![2,1,3], vec![2,3,1], vec![3,1,2], vec![3,2,1]]
+    );
+    assert_eq!(permutations(&[]), vec![Vec::<Word>::new()]);
+}
+// Every ordering of the given values, e.g. for trying phase settings
+pub fn permutations(values: &[Word]) -> Vec<Vec<Word>> {
+    if values.is_empty() {
+        return vec![Vec::new()]
+    }
+    let mut result = Vec::new();
+    for i in 0..values.len() {
+        let mut rest = values.to_vec();
+        let first = rest.remove(i);
+        for mut permutation in permutations(&rest) {
+            permutation.insert(0, first);
+            result.push(permutation);
+        }
+    }
+    result
+}
+
+#[test]
+fn test_run_chain() {
+    // Examples from day 7
+    let tape = [3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0];
+    assert_eq!(run_chain(&tape, &[4,3,2,1,0], false), Ok(Some(43210)));
+
+    let tape = [3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5];
+    assert_eq!(run_chain(&tape, &[9,8,7,6,5], true), Ok(Some(139629729)));
+}
+// Runs a chain of machines with the given phase settings, sending an initial
+// signal of 0 to the first machine, and returns the last signal output by
+// the last machine
+pub fn run_chain(tape: &[Word], phases: &[Word], cyclic: bool) -> Result<Option<Word>, IntcodeError> {
+    let mut network = Network::chain(tape, phases, cyclic);
+    if phases.is_empty() {
+        return Ok(None)
+    }
+    network.push_input(0, 0);
+    network.run()?;
+    Ok(network.last_output(phases.len() - 1))
+}
+
+#[test]
+fn test_max_signal() {
+    let tape = [3,23,3,24,1002,24,10,24,1002,23,-1,23,101,5,23,23,1,24,23,23,4,23,99,0,0];
+    assert_eq!(max_signal(&tape, &[0,1,2,3,4], false), Ok(Some((vec![0,1,2,3,4], 54321))));
+
+    let tape = [
+        3,52,1001,52,-5,52,3,53,1,52,56,54,1007,54,5,55,1005,55,26,1001,54,
+        -5,54,1105,1,12,1,53,54,53,1008,54,0,55,1001,55,1,55,2,53,55,53,4,
+        53,1001,56,-1,56,1005,56,6,99,0,0,0,0,10
+    ];
+    assert_eq!(max_signal(&tape, &[5,6,7,8,9], true), Ok(Some((vec![9,7,8,5,6], 18216))));
+}
+// Tries every permutation of the phase settings and returns the one which
+// produces the highest signal, along with that signal
+pub fn max_signal(tape: &[Word], phases: &[Word], cyclic: bool) -> Result<Option<(Vec<Word>, Word)>, IntcodeError> {
+    let mut best: Option<(Vec<Word>, Word)> = None;
+    for permutation in permutations(phases) {
+        if let Some(signal) = run_chain(tape, &permutation, cyclic)? {
+            match best {
+                Some((_, x)) if x >= signal => {},
+                _ => { best = Some((permutation, signal)) },
+            }
+        }
+    }
+    Ok(best)
+}
@@ -0,0 +1,207 @@
+// Profiling for Intcode runs. A Profile is a TraceSink, so it's filled in by
+// running a machine with run_traced(), and counts how often each instruction
+// ran, which loops were hottest, and which addresses were executed, read or
+// written along the way
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use super::trace::{TraceEvent, TraceSink};
+use super::{Opcode, Word};
+
+// A loop, as seen from a jump taken backwards from end to start
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub struct Loop {
+    pub start: usize,
+    // Address of the jump which closes the loop
+    pub end: usize,
+    // Times the jump was taken
+    pub iterations: u64,
+    // Instructions executed between start and end, inclusive, over the run
+    pub instructions: u64,
+}
+
+#[derive(Debug)]
+#[derive(Clone, Default)]
+pub struct Profile {
+    // Executions of each instruction, by address
+    pub counts: BTreeMap<usize, u64>,
+    // Raw value of the instruction most recently executed at each address
+    pub instructions: BTreeMap<usize, Word>,
+    // Executions of each opcode, by mnemonic
+    pub opcodes: BTreeMap<String, u64>,
+    // Backward jumps taken, by (target, jump address)
+    pub back_jumps: BTreeMap<(usize, usize), u64>,
+    // Every address holding part of an executed instruction
+    pub executed: BTreeSet<usize>,
+    pub read: BTreeSet<usize>,
+    pub written: BTreeSet<usize>,
+    // The last jump executed, if the instruction before this one was a jump
+    last_jump: Option<(usize, usize)>,
+}
+
+#[test]
+fn test_profile() {
+    let countdown = super::fixtures::COUNTDOWN;
+    let mut profile = Profile::new();
+    super::run_program_traced(&countdown, &[], &mut profile).unwrap();
+
+    assert_eq!(profile.total(), 11);
+    assert_eq!(profile.counts.get(&4), Some(&3));
+    assert_eq!(profile.counts.get(&13), Some(&1));
+    assert_eq!(profile.opcodes.get("OUT"), Some(&3));
+    assert_eq!(profile.opcodes.get("JNZ"), Some(&3));
+    assert_eq!(profile.loops(), vec![Loop { start: 4, end: 10, iterations: 2, instructions: 9 }]);
+
+    assert_eq!(profile.executed, (0..14).collect());
+    assert_eq!(profile.read, [14].iter().copied().collect());
+    assert_eq!(profile.written, [14].iter().copied().collect());
+    assert_eq!(profile.coverage_map(16), "0000: XXXXXXXXXXXXXXW.\n");
+    assert_eq!(
+        profile.to_json(),
+        concat!(
+            r#"{"total":11,"counts":[[0,1],[4,3],[6,3],[10,3],[13,1]],"opcodes":{"ADD":4,"JNZ":3,"OUT":3,"STOP":1},"#,
+            r#""loops":[{"start":4,"end":10,"iterations":2,"instructions":9}],"#,
+            r#""executed":[0,1,2,3,4,5,6,7,8,9,10,11,12,13],"read":[14],"written":[14]}"#,
+        )
+    );
+}
+impl Profile {
+    pub fn new() -> Profile {
+        Profile::default()
+    }
+
+    // Total instructions executed
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    // Every loop seen, hottest first by instructions executed within it
+    pub fn loops(&self) -> Vec<Loop> {
+        let mut loops: Vec<Loop> = self.back_jumps.iter()
+            .map(|((start, end), iterations)| Loop {
+                start: *start,
+                end: *end,
+                iterations: *iterations,
+                instructions: self.counts.range(*start..=*end).map(|(_, x)| x).sum(),
+            })
+            .collect();
+        loops.sort_by(|a, b| b.instructions.cmp(&a.instructions).then(a.start.cmp(&b.start)));
+        loops
+    }
+
+    // Addresses by execution count, most executed first
+    pub fn hottest(&self) -> Vec<(usize, u64)> {
+        let mut hottest: Vec<(usize, u64)> = self.counts.iter().map(|(a, c)| (*a, *c)).collect();
+        hottest.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        hottest
+    }
+
+    // One character per address, 64 to a line:
+    //   X executed, M executed and written (self-modifying code),
+    //   W written, R read, . untouched
+    pub fn coverage_map(&self, size: usize) -> String {
+        let mut map = String::new();
+        for address in 0..size {
+            if address % 64 == 0 {
+                if address > 0 {
+                    map.push('\n');
+                }
+                write!(map, "{:04}: ", address).unwrap();
+            }
+            let executed = self.executed.contains(&address);
+            let written = self.written.contains(&address);
+            map.push(match (executed, written) {
+                (true, true)                      => 'M',
+                (true, false)                     => 'X',
+                (false, true)                     => 'W',
+                _ if self.read.contains(&address) => 'R',
+                _                                 => '.',
+            });
+        }
+        if size > 0 {
+            map.push('\n');
+        }
+        map
+    }
+
+    // A human-readable report: opcode totals, the top hottest addresses and
+    // loops, then a coverage map of the first size addresses
+    pub fn report(&self, top: usize, size: usize) -> String {
+        let mut report = String::new();
+        let total = self.total();
+        let percent = |x: u64| 100.0 * x as f64 / std::cmp::max(total, 1) as f64;
+        writeln!(report, "{} instructions executed", total).unwrap();
+
+        writeln!(report, "\nOpcode     Count      %").unwrap();
+        let mut opcodes: Vec<(&String, &u64)> = self.opcodes.iter().collect();
+        opcodes.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        for (mnemonic, count) in opcodes {
+            writeln!(report, "{:<6} {:>9} {:>6.2}", mnemonic, count, percent(*count)).unwrap();
+        }
+
+        writeln!(report, "\nAddress    Count      %  Instruction").unwrap();
+        for (address, count) in self.hottest().into_iter().take(top) {
+            let raw = self.instructions[&address];
+            let mnemonic = super::get_opcode(&raw).map_or("?", |x| x.mnemonic());
+            writeln!(report, "{:04}   {:>9} {:>6.2}  {} ({})", address, count, percent(count), mnemonic, raw).unwrap();
+        }
+
+        writeln!(report, "\nLoop          Iterations  Instructions      %").unwrap();
+        for l in self.loops().into_iter().take(top) {
+            writeln!(
+                report,
+                "{:04}-{:04} {:>13} {:>13} {:>6.2}",
+                l.start, l.end, l.iterations, l.instructions, percent(l.instructions)
+            ).unwrap();
+        }
+
+        writeln!(report, "\nCoverage (X executed, M self-modified, W written, R read)").unwrap();
+        report.push_str(&self.coverage_map(size));
+        report
+    }
+
+    // The whole profile as a single JSON object
+    pub fn to_json(&self) -> String {
+        let counts: Vec<String> = self.counts.iter().map(|(a, c)| format!("[{},{}]", a, c)).collect();
+        let opcodes: Vec<String> = self.opcodes.iter().map(|(m, c)| format!(r#""{}":{}"#, m, c)).collect();
+        let loops: Vec<String> = self.loops().iter()
+            .map(|x| format!(
+                r#"{{"start":{},"end":{},"iterations":{},"instructions":{}}}"#,
+                x.start, x.end, x.iterations, x.instructions
+            ))
+            .collect();
+        let list = |set: &BTreeSet<usize>| set.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(",");
+        format!(
+            r#"{{"total":{},"counts":[{}],"opcodes":{{{}}},"loops":[{}],"executed":[{}],"read":[{}],"written":[{}]}}"#,
+            self.total(),
+            counts.join(","),
+            opcodes.join(","),
+            loops.join(","),
+            list(&self.executed),
+            list(&self.read),
+            list(&self.written),
+        )
+    }
+}
+
+impl TraceSink for Profile {
+    fn record(&mut self, event: &TraceEvent) {
+        if let Some((jump, target)) = self.last_jump.take() {
+            if event.ip == target && target <= jump {
+                *self.back_jumps.entry((target, jump)).or_insert(0) += 1;
+            }
+        }
+        if event.opcode == Some(Opcode::Jnz) || event.opcode == Some(Opcode::Jz) {
+            self.last_jump = Some((event.ip, event.args[1] as usize));
+        }
+
+        *self.counts.entry(event.ip).or_insert(0) += 1;
+        self.instructions.insert(event.ip, event.instruction);
+        *self.opcodes.entry(event.mnemonic.clone()).or_insert(0) += 1;
+        self.executed.extend(event.ip..=event.ip + event.args.len());
+        self.read.extend(event.reads.iter());
+        self.written.extend(event.writes.iter().map(|(address, _)| *address));
+    }
+}
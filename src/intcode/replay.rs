@@ -0,0 +1,443 @@
+// Deterministic record and replay of Intcode sessions, so that a run which
+// misbehaved (e.g. an interactive robot or arcade harness) can be reproduced
+// exactly.
+//
+// A Session is a TraceSink which logs every input consumed and output
+// produced, along with the step (the number of instructions executed before
+// it) and ip. replay() runs a machine from the same starting state, feeding it
+// the recorded input, and checks it produces the same output at the same
+// points. A Recorder writes a session out as it goes instead, so that a run
+// which crashes or is interrupted isn't lost. Sessions are saved as plain
+// text:
+//   # intcode session v1
+//   0 0 in 4
+//   3 9 out 8
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+
+use super::device::Device;
+use super::trace::{TraceEvent, TraceSink};
+use super::{IntcodeError, Machine, Status, Word};
+
+pub const HEADER: &str = "# intcode session v1";
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Io {
+    Input(Word),
+    Output(Word),
+}
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub struct Entry {
+    pub step: usize,
+    pub ip: usize,
+    pub io: Io,
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.io {
+            Io::Input(x)  => write!(f, "{} {} in {}", self.step, self.ip, x),
+            Io::Output(x) => write!(f, "{} {} out {}", self.step, self.ip, x),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SessionError {
+    Io(io::Error),
+    // Not a session we can make sense of, with a description of why
+    Format(String),
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SessionError::Io(e)     => write!(f, "{}", e),
+            SessionError::Format(x) => write!(f, "invalid session: {}", x),
+        }
+    }
+}
+
+impl Error for SessionError {}
+
+impl From<io::Error> for SessionError {
+    fn from(e: io::Error) -> Self {
+        SessionError::Io(e)
+    }
+}
+
+// Everything that went in and out of a machine, in order
+#[derive(Debug)]
+#[derive(Clone, Default)]
+pub struct Session {
+    pub entries: Vec<Entry>,
+    // Instructions executed so far while recording
+    steps: usize,
+}
+
+// What an instruction executed at the given step took in and put out
+fn entries(event: &TraceEvent, step: usize) -> [Option<Entry>; 2] {
+    let ip = event.ip;
+    [
+        event.input.map(|x| Entry { step, ip, io: Io::Input(x) }),
+        event.output.map(|x| Entry { step, ip, io: Io::Output(x) }),
+    ]
+}
+
+impl TraceSink for Session {
+    fn record(&mut self, event: &TraceEvent) {
+        self.entries.extend(entries(event, self.steps).iter().flatten().copied());
+        self.steps += 1;
+    }
+}
+
+#[cfg(test)]
+use super::fixtures::ECHO;
+
+#[test]
+fn test_record() {
+    let mut machine = Machine::new(&ECHO);
+    machine.extend_input(vec![4, 21, 0]);
+    let mut session = Session::new();
+    assert_eq!(machine.run_traced(&mut session), Ok(Status::Halted));
+    assert_eq!(session.inputs(), vec![4, 21, 0]);
+    assert_eq!(session.outputs(), vec![8, 42]);
+
+    let text = session.to_text();
+    assert_eq!(text, format!("{}\n0 0 in 4\n3 9 out 8\n5 0 in 21\n8 9 out 42\n10 0 in 0\n", HEADER));
+    assert_eq!(Session::from_text(&text).unwrap().entries, session.entries);
+
+    assert!(Session::from_text("0 0 in 4\n").is_err());
+    assert!(Session::from_text(&format!("{}\n0 0 sideways 4\n", HEADER)).is_err());
+    assert!(Session::from_text(&format!("{}\n0 0 in\n", HEADER)).is_err());
+}
+impl Session {
+    pub fn new() -> Session {
+        Session::default()
+    }
+
+    // Every input recorded, in order
+    pub fn inputs(&self) -> Vec<Word> {
+        self.entries.iter()
+            .filter_map(|x| match x.io {
+                Io::Input(x)  => Some(x),
+                Io::Output(_) => None,
+            })
+            .collect()
+    }
+
+    pub fn outputs(&self) -> Vec<Word> {
+        self.entries.iter()
+            .filter_map(|x| match x.io {
+                Io::Input(_)  => None,
+                Io::Output(x) => Some(x),
+            })
+            .collect()
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{}\n", HEADER);
+        for entry in self.entries.iter() {
+            text.push_str(&format!("{}\n", entry));
+        }
+        text
+    }
+
+    pub fn from_text(text: &str) -> Result<Session, SessionError> {
+        let mut lines = text.lines();
+        if lines.next() != Some(HEADER) {
+            return Err(SessionError::Format(format!("expected \"{}\" on the first line", HEADER)))
+        }
+        let mut session = Session::new();
+        for (i, line) in lines.enumerate().filter(|(_, x)| !x.trim().is_empty()) {
+            let invalid = || SessionError::Format(format!("line {}: {}", i + 2, line));
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() != 4 {
+                return Err(invalid())
+            }
+            let step = fields[0].parse().map_err(|_| invalid())?;
+            let ip = fields[1].parse().map_err(|_| invalid())?;
+            let value = fields[3].parse().map_err(|_| invalid())?;
+            let io = match fields[2] {
+                "in"  => Io::Input(value),
+                "out" => Io::Output(value),
+                _     => { return Err(invalid()) },
+            };
+            session.entries.push(Entry { step, ip, io });
+        }
+        Ok(session)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SessionError> {
+        fs::write(path, self.to_text())?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Session, SessionError> {
+        Session::from_text(&fs::read_to_string(path)?)
+    }
+}
+
+// Writes a session out one entry at a time as it's recorded, flushing each,
+// e.g. to a file. As with JsonLines, a failed write mustn't stop the machine,
+// so the first error is kept (and nothing more written) until finish()
+// reports it
+pub struct Recorder<W: Write> {
+    writer: W,
+    // Instructions executed so far
+    steps: usize,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> Recorder<W> {
+    // Starts a session, writing its header straight away
+    pub fn new(mut writer: W) -> io::Result<Recorder<W>> {
+        writeln!(writer, "{}", HEADER)?;
+        writer.flush()?;
+        Ok(Recorder { writer, steps: 0, error: None })
+    }
+
+    // Returns the writer if every entry was written out
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(e) = self.error.take() {
+            return Err(e)
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[test]
+fn test_recorder() {
+    let mut machine = Machine::new(&ECHO);
+    machine.extend_input(vec![4, 21]);
+    let mut recorder = Recorder::new(Vec::new()).unwrap();
+    assert_eq!(machine.run_traced(&mut recorder), Ok(Status::WaitingForInput));
+    // Everything so far is already written
+    let expected = format!("{}\n0 0 in 4\n3 9 out 8\n5 0 in 21\n8 9 out 42\n", HEADER);
+    assert_eq!(String::from_utf8(recorder.writer.clone()).unwrap(), expected);
+
+    // Steps carry on being counted from where they left off
+    machine.push_input(0);
+    assert_eq!(machine.run_traced(&mut recorder), Ok(Status::Halted));
+    let text = String::from_utf8(recorder.finish().unwrap()).unwrap();
+    let mut session = Session::new();
+    let mut machine = Machine::new(&ECHO);
+    machine.extend_input(vec![4, 21, 0]);
+    machine.run_traced(&mut session).unwrap();
+    assert_eq!(text, session.to_text());
+
+    // Accepts the header and one entry, then fails
+    use super::fixtures::Full;
+    let mut machine = Machine::new(&ECHO);
+    machine.extend_input(vec![4, 21, 0]);
+    let mut recorder = Recorder::new(Full(2)).unwrap();
+    assert_eq!(machine.run_traced(&mut recorder), Ok(Status::Halted));
+    assert_eq!(recorder.finish().err().map(|x| x.to_string()), Some("disk full".to_string()));
+    assert!(Recorder::new(Full(0)).is_err());
+}
+impl<W: Write> TraceSink for Recorder<W> {
+    fn record(&mut self, event: &TraceEvent) {
+        for entry in entries(event, self.steps).iter().flatten() {
+            if self.error.is_none() {
+                self.error = writeln!(self.writer, "{}", entry).and_then(|_| self.writer.flush()).err();
+            }
+        }
+        self.steps += 1;
+    }
+}
+
+// Where a replay first differed from the recording
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum Divergence {
+    // Produced output which doesn't match what was recorded next
+    Output { step: usize, ip: usize, actual: Word, expected: Option<Entry> },
+    // Asked for input where the recording has something else next
+    Input { step: usize, ip: usize, expected: Entry },
+    // Ran an instruction without the input or output recorded for it
+    Missed { step: usize, ip: usize, expected: Entry },
+    // Halted with recorded input or output still to come
+    Stopped { step: usize, ip: usize, expected: Entry },
+    // Failed. Errors aren't recorded, so this is reported even if the
+    // recorded run failed in the same way
+    Error { step: usize, ip: usize, error: IntcodeError },
+}
+
+impl Divergence {
+    pub fn step(&self) -> usize {
+        match self {
+            Divergence::Output { step, .. }  => *step,
+            Divergence::Input { step, .. }   => *step,
+            Divergence::Missed { step, .. }  => *step,
+            Divergence::Stopped { step, .. } => *step,
+            Divergence::Error { step, .. }   => *step,
+        }
+    }
+
+    pub fn ip(&self) -> usize {
+        match self {
+            Divergence::Output { ip, .. }  => *ip,
+            Divergence::Input { ip, .. }   => *ip,
+            Divergence::Missed { ip, .. }  => *ip,
+            Divergence::Stopped { ip, .. } => *ip,
+            Divergence::Error { ip, .. }   => *ip,
+        }
+    }
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "step {}, ip {}: ", self.step(), self.ip())?;
+        match self {
+            Divergence::Output { actual, expected: Some(x), .. } => {
+                write!(f, "output {}, but the recording has \"{}\"", actual, x)
+            },
+            Divergence::Output { actual, expected: None, .. } => {
+                write!(f, "output {} after the end of the recording", actual)
+            },
+            Divergence::Input { expected, .. } => {
+                write!(f, "asked for input, but the recording has \"{}\"", expected)
+            },
+            Divergence::Missed { expected, .. } => {
+                write!(f, "no input or output, but the recording has \"{}\"", expected)
+            },
+            Divergence::Stopped { expected, .. } => {
+                write!(f, "halted, but the recording has \"{}\"", expected)
+            },
+            Divergence::Error { error, .. } => write!(f, "{}", error),
+        }
+    }
+}
+
+// Feeds a machine recorded input, checking its output against the recording
+// as it goes. The first mismatch is kept, and any input asked for after it
+// is refused so the machine stops
+struct Player<'a> {
+    entries: &'a [Entry],
+    next: usize,
+    step: usize,
+    ip: usize,
+    divergence: Option<Divergence>,
+}
+
+impl<'a> Device for Player<'a> {
+    fn read(&mut self) -> Option<Word> {
+        match self.entries.get(self.next) {
+            Some(Entry { step, ip, io: Io::Input(x) }) if (*step, *ip) == (self.step, self.ip) => {
+                self.next += 1;
+                Some(*x)
+            },
+            // The recording stopped here, waiting for input
+            None => None,
+            Some(entry) => {
+                let (step, ip) = (self.step, self.ip);
+                self.divergence.get_or_insert(Divergence::Input { step, ip, expected: *entry });
+                None
+            },
+        }
+    }
+
+    fn write(&mut self, value: Word) {
+        match self.entries.get(self.next) {
+            Some(Entry { step, ip, io: Io::Output(x) }) if (*step, *ip, *x) == (self.step, self.ip, value) => {
+                self.next += 1;
+            },
+            expected => {
+                let (step, ip) = (self.step, self.ip);
+                let divergence = Divergence::Output { step, ip, actual: value, expected: expected.copied() };
+                self.divergence.get_or_insert(divergence);
+            },
+        }
+    }
+}
+
+#[test]
+fn test_replay() {
+    let mut machine = Machine::new(&ECHO);
+    machine.extend_input(vec![4, 21, 0]);
+    let mut session = Session::new();
+    machine.run_traced(&mut session).unwrap();
+    assert_eq!(replay(&mut Machine::new(&ECHO), &session), Ok(Status::Halted));
+
+    // Tripling instead of doubling
+    let mut tape = ECHO;
+    tape[7] = 3;
+    let divergence = replay(&mut Machine::new(&tape), &session).unwrap_err();
+    assert_eq!(
+        divergence,
+        Divergence::Output { step: 3, ip: 9, actual: 12, expected: Some(session.entries[1]) }
+    );
+    assert_eq!(divergence.to_string(), "step 3, ip 9: output 12, but the recording has \"3 9 out 8\"");
+
+    // Recorded as stopping for input before the end
+    let mut partial = session.clone();
+    partial.entries.truncate(2);
+    let mut machine = Machine::new(&ECHO);
+    assert_eq!(replay(&mut machine, &partial), Ok(Status::WaitingForInput));
+    assert_eq!(machine.output(), &[] as &[Word]);
+
+    // Input which came at a different point
+    let mut early = session.clone();
+    early.entries[2].step = 4;
+    assert_eq!(
+        replay(&mut Machine::new(&ECHO), &early),
+        Err(Divergence::Missed { step: 4, ip: 11, expected: early.entries[2] })
+    );
+    let mut late = session.clone();
+    late.entries[2].step = 6;
+    assert_eq!(
+        replay(&mut Machine::new(&ECHO), &late),
+        Err(Divergence::Input { step: 5, ip: 0, expected: late.entries[2] })
+    );
+
+    // More recorded after the program halted
+    let mut longer = session.clone();
+    longer.entries.push(Entry { step: 13, ip: 14, io: Io::Output(1) });
+    assert_eq!(
+        replay(&mut Machine::new(&ECHO), &longer),
+        Err(Divergence::Stopped { step: 12, ip: 14, expected: longer.entries[5] })
+    );
+}
+// Replays a session against a machine, which should be in the same state as
+// the one recorded was when recording began (e.g. new, or restored from a
+// snapshot). Returns the status the machine finished with if everything
+// matched: Halted, or WaitingForInput if the recording stopped before the
+// program did
+pub fn replay(machine: &mut Machine, session: &Session) -> Result<Status, Divergence> {
+    let mut player = Player { entries: &session.entries, next: 0, step: 0, ip: 0, divergence: None };
+    loop {
+        player.ip = machine.ip();
+        let result = machine.step_with(&mut player);
+        if let Some(x) = player.divergence.take() {
+            return Err(x)
+        }
+        let (step, ip) = (player.step, player.ip);
+        match result.map_err(|error| Divergence::Error { step, ip, error })? {
+            Status::Running | Status::Output(_) => {
+                match session.entries.get(player.next) {
+                    Some(entry) if entry.step <= step => {
+                        return Err(Divergence::Missed { step, ip, expected: *entry })
+                    },
+                    _ => { player.step += 1 },
+                }
+            },
+            status => {
+                if let (Status::Halted, Some(entry)) = (status, session.entries.get(player.next)) {
+                    return Err(Divergence::Stopped { step, ip, expected: *entry })
+                }
+                return Ok(status)
+            },
+        }
+    }
+}
@@ -0,0 +1,246 @@
+// Brute-force search over a program's inputs, for puzzles like day 2 which
+// ask which values patched into the tape (its noun and verb) make it produce
+// a particular result.
+//
+// Every combination of values is tried, with the last address patched
+// varying fastest, as in nested loops. Runs are shared out between threads,
+// and runs which fail or don't halt (within DEFAULT_MAX_STEPS instructions,
+// unless other limits are set) never match
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use super::{Limits, Machine, Memory, Status, Word};
+
+// Instructions each run gets unless set_limits() says otherwise, so that a
+// combination which never halts can't stall the search
+pub const DEFAULT_MAX_STEPS: usize = 1_000_000;
+
+// How a run finished
+pub struct Outcome {
+    pub memory: Memory,
+    pub output: Vec<Word>,
+}
+
+// There are more combinations of values to try than fit in a usize
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub struct TooLarge;
+
+impl fmt::Display for TooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "too many combinations to search")
+    }
+}
+
+impl Error for TooLarge {}
+
+pub struct Search<'a> {
+    tape: &'a [Word],
+    // Each address to patch, along with the first value to try there and the
+    // number of values
+    patches: Vec<(usize, Word, usize)>,
+    // Number of combinations, i.e. the product of the numbers of values
+    size: usize,
+    input: Vec<Word>,
+    limits: Limits,
+    threads: usize,
+}
+
+#[test]
+fn test_search() {
+    // Stores ([9] + [10]) * [12] at address 0
+    let tape = [1,9,10,11,2,11,12,0,99,0,0,0,0];
+    let mut search = Search::new(&tape);
+    search.patch(9, 0..=9).unwrap();
+    search.patch(10, 1..=9).unwrap();
+    search.patch(12, 0..=1).unwrap();
+    assert_eq!(search.size(), 10 * 9 * 2);
+    let is_twelve = |x: &Outcome| x.memory.get(0) == 12;
+
+    let mut all = Vec::new();
+    for threads in [1, 3, 8].iter() {
+        search.set_threads(*threads);
+        assert_eq!(search.first(is_twelve), Some(vec![3, 9, 1]));
+        all.push(search.all(is_twelve));
+    }
+    assert_eq!(all[0].len(), 7);
+    assert_eq!(all[0][1], vec![4, 8, 1]);
+    assert_eq!(all[0], all[1]);
+    assert_eq!(all[0], all[2]);
+    assert!(search.first(|x| x.memory.get(0) == 1000).is_none());
+
+    // Jumps back to itself for ever unless [1] is 0, which by default is
+    // given up on after DEFAULT_MAX_STEPS
+    let mut search = Search::new(&[1105,0,0,99]);
+    search.patch(1, 0..=1).unwrap();
+    assert_eq!(search.all(|_| true), vec![vec![0]]);
+    search.set_limits(Limits { max_steps: None, detect_loops: true });
+    assert_eq!(search.all(|_| true), vec![vec![0]]);
+}
+
+#[test]
+fn test_search_day2() {
+    // Day 2 part 2, over the full range of nouns and verbs
+    let tape: Vec<Word> = crate::input_to_vec_t_fromstr(include_str!("../bin/day2/input"), ',');
+    let mut search = Search::new(&tape);
+    search.patch(1, 0..=99).unwrap();
+    search.patch(2, 0..=99).unwrap();
+    assert_eq!(search.first(|x| x.memory.get(0) == 19690720), Some(vec![94, 25]));
+
+    // Predicates can look at output as well as memory
+    let mut search = Search::new(&[3,9,1,9,10,9,4,9,99,0,0]);
+    search.push_input(30);
+    search.patch(10, -50..=50).unwrap();
+    assert_eq!(search.all(|x| x.output == [42]), vec![vec![12]]);
+}
+
+#[test]
+fn test_search_size() {
+    let tape = [3,9,1,9,10,9,4,9,99,0,0];
+    let mut search = Search::new(&tape);
+    search.push_input(30);
+    // Wide ranges are fine as long as they can be counted, and aren't
+    // walked through to find a value
+    search.patch(10, 12..=Word::MAX).unwrap();
+    assert_eq!(search.size(), Word::MAX as usize - 11);
+    assert_eq!(search.first(|x| x.output == [42]), Some(vec![12]));
+    assert_eq!(search.patch(9, 0..=2), Err(TooLarge));
+    assert_eq!(search.size(), Word::MAX as usize - 11);
+
+    let mut search = Search::new(&tape);
+    assert_eq!(search.patch(10, Word::MIN..=Word::MAX), Err(TooLarge));
+    let (first, last) = (1, 0);
+    search.patch(10, first..=last).unwrap();
+    assert_eq!(search.size(), 0);
+    assert_eq!(search.all(|_| true), Vec::<Vec<Word>>::new());
+}
+impl<'a> Search<'a> {
+    // A search with nothing to patch yet, using one thread per CPU
+    pub fn new(tape: &'a [Word]) -> Search<'a> {
+        Search {
+            tape,
+            patches: Vec::new(),
+            size: 1,
+            input: Vec::new(),
+            limits: Limits { max_steps: Some(DEFAULT_MAX_STEPS), detect_loops: false },
+            threads: thread::available_parallelism().map_or(1, |x| x.get()),
+        }
+    }
+
+    // Fails if the values to try, or the combinations of them with those
+    // for other addresses, can't be counted in a usize
+    pub fn patch(&mut self, address: usize, values: RangeInclusive<Word>) -> Result<(), TooLarge> {
+        let (start, end) = values.into_inner();
+        let count = match end.checked_sub(start) {
+            _ if end < start => 0,
+            Some(x)          => usize::try_from(x).ok().and_then(|x| x.checked_add(1)).ok_or(TooLarge)?,
+            None             => { return Err(TooLarge) },
+        };
+        self.size = self.size.checked_mul(count).ok_or(TooLarge)?;
+        self.patches.push((address, start, count));
+        Ok(())
+    }
+
+    // Input given to every run
+    pub fn push_input(&mut self, value: Word) {
+        self.input.push(value);
+    }
+
+    // Limits for each run, in place of DEFAULT_MAX_STEPS. Without a step
+    // limit, a combination which never halts stalls the search for ever
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = std::cmp::max(threads, 1);
+    }
+
+    // Number of combinations to try
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    // The combination of values with the given index
+    fn values(&self, mut index: usize) -> Vec<Word> {
+        let mut values = vec![0; self.patches.len()];
+        for (i, (_, start, count)) in self.patches.iter().enumerate().rev() {
+            values[i] = start + (index % count) as Word;
+            index /= count;
+        }
+        values
+    }
+
+    // Runs the program with the given values patched in, returning how it
+    // finished if it halted
+    fn run(&self, values: &[Word]) -> Option<Outcome> {
+        let mut machine = Machine::new(self.tape);
+        for ((address, _, _), value) in self.patches.iter().zip(values.iter()) {
+            machine.patch(*address, *value);
+        }
+        machine.extend_input(self.input.iter().copied());
+        match machine.run_limited(&self.limits) {
+            Ok(Status::Halted) => {},
+            _                  => { return None },
+        }
+        let output = machine.take_output();
+        Some(Outcome { memory: machine.into_memory(), output })
+    }
+
+    // Hands out indices round-robin to each thread, which tries them in order
+    // until its next index reaches stop
+    fn search<P>(&self, predicate: &P, stop: &AtomicUsize, first: bool) -> Vec<(usize, Vec<Word>)>
+        where
+            P: Fn(&Outcome) -> bool + Sync,
+    {
+        let size = self.size();
+        let mut found = thread::scope(|scope| {
+            let handles: Vec<_> = (0..self.threads)
+                .map(|start| scope.spawn(move || {
+                    let mut found = Vec::new();
+                    let mut index = start;
+                    while index < std::cmp::min(size, stop.load(Ordering::Relaxed)) {
+                        let values = self.values(index);
+                        if self.run(&values).is_some_and(|x| predicate(&x)) {
+                            found.push((index, values));
+                            if first {
+                                stop.fetch_min(index, Ordering::Relaxed);
+                                break
+                            }
+                        }
+                        index += self.threads;
+                    }
+                    found
+                }))
+                .collect();
+            handles.into_iter().flat_map(|x| x.join().unwrap()).collect::<Vec<(usize, Vec<Word>)>>()
+        });
+        found.sort();
+        found
+    }
+
+    // Every combination of values (in the same order as the patches) for
+    // which the predicate holds
+    pub fn all<P>(&self, predicate: P) -> Vec<Vec<Word>>
+        where
+            P: Fn(&Outcome) -> bool + Sync,
+    {
+        let stop = AtomicUsize::new(usize::MAX);
+        self.search(&predicate, &stop, false).into_iter().map(|(_, x)| x).collect()
+    }
+
+    // The first combination of values for which the predicate holds. Always
+    // the same one all() would list first, however many threads are used
+    pub fn first<P>(&self, predicate: P) -> Option<Vec<Word>>
+        where
+            P: Fn(&Outcome) -> bool + Sync,
+    {
+        let stop = AtomicUsize::new(usize::MAX);
+        self.search(&predicate, &stop, true).into_iter().next().map(|(_, x)| x)
+    }
+}
@@ -0,0 +1,330 @@
+// A frozen copy of a machine's state, for forking a machine at a decision
+// point and trying alternatives, or saving a long run to resume later.
+//
+// On disk, a snapshot is a single JSON object:
+//   {"version":1,"ip":4,"relative_base":0,"status":"running",
+//    "memory":[3,9,...],"far":[[1048577,5]],"input":[1],"output":[]}
+// where memory is the contiguous part of memory from address 0, and far holds
+// any (address, value) pairs stored beyond it. Output(x) status is written as
+// {"output":x}. Which addresses the program had touched isn't saved
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::{Memory, Status, Word};
+
+// Bumped whenever the on-disk format changes incompatibly
+pub const VERSION: Word = 1;
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Snapshot {
+    pub memory: Memory,
+    pub ip: usize,
+    pub relative_base: Word,
+    pub status: Status,
+    // Input provided but not yet read, and output not yet taken
+    pub input: VecDeque<Word>,
+    pub output: Vec<Word>,
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    // Not a snapshot we can make sense of, with a description of why
+    Format(String),
+    UnsupportedVersion(Word),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::Io(e)                 => write!(f, "{}", e),
+            SnapshotError::Format(x)             => write!(f, "invalid snapshot: {}", x),
+            SnapshotError::UnsupportedVersion(x) => {
+                write!(f, "unsupported snapshot version {} (expected {})", x, VERSION)
+            },
+        }
+    }
+}
+
+impl Error for SnapshotError {}
+
+impl From<io::Error> for SnapshotError {
+    fn from(e: io::Error) -> Self {
+        SnapshotError::Io(e)
+    }
+}
+
+fn format_error<T>(message: &str) -> Result<T, SnapshotError> {
+    Err(SnapshotError::Format(message.to_string()))
+}
+
+fn join(values: &[Word]) -> String {
+    let values: Vec<String> = values.iter().map(|x| x.to_string()).collect();
+    values.join(",")
+}
+
+#[test]
+fn test_to_json() {
+    let mut machine = super::Machine::new(&[3,7,4,7,99]);
+    machine.extend_input(vec![11, 12]);
+    machine.run_until_output().unwrap();
+    assert_eq!(
+        machine.snapshot().to_json(),
+        r#"{"version":1,"ip":4,"relative_base":0,"status":{"output":11},"memory":[3,7,4,7,99,0,0,11],"far":[],"input":[12],"output":[11]}"#
+    );
+}
+
+#[test]
+fn test_json_round_trip() {
+    let mut machine = super::Machine::new(&[109,5,1201,-5,0,1048576,3,10,99]);
+    machine.push_input(-7);
+    machine.run().unwrap();
+    let json = machine.snapshot().to_json();
+    let snapshot = Snapshot::from_json(&json).unwrap();
+    assert_eq!(snapshot.to_json(), json);
+    assert_eq!(snapshot.memory.get(1048576), 109);
+    assert_eq!(snapshot.memory.get(10), -7);
+    assert_eq!(snapshot.status, Status::Halted);
+
+    let json = r#"{"version":1, "ip":0, "relative_base":0, "status":"waiting_for_input",
+        "memory":[3,0,99], "far":[], "input":[], "output":[]}"#;
+    let mut machine = super::Machine::from_snapshot(&Snapshot::from_json(json).unwrap());
+    machine.push_input(5);
+    assert_eq!(machine.run(), Ok(Status::Halted));
+    assert_eq!(machine.memory().get(0), 5);
+}
+
+#[test]
+fn test_from_json_errors() {
+    let error = |json| Snapshot::from_json(json).unwrap_err().to_string();
+    assert_eq!(
+        error(r#"{"version":2}"#),
+        "unsupported snapshot version 2 (expected 1)"
+    );
+    assert_eq!(
+        error(r#"{"version":1,"ip":0}"#),
+        "invalid snapshot: missing field \"status\""
+    );
+    assert_eq!(error("[1,2"), "invalid snapshot: expected ',' or ']' at offset 4");
+}
+impl Snapshot {
+    pub fn to_json(&self) -> String {
+        let status = match self.status {
+            Status::Running         => r#""running""#.to_string(),
+            Status::Halted          => r#""halted""#.to_string(),
+            Status::WaitingForInput => r#""waiting_for_input""#.to_string(),
+            Status::Output(x)       => format!(r#"{{"output":{}}}"#, x),
+        };
+        let far: Vec<String> = self.memory.far_values().iter()
+            .map(|(addr, value)| format!("[{},{}]", addr, value))
+            .collect();
+        let input: Vec<Word> = self.input.iter().copied().collect();
+        format!(
+            r#"{{"version":{},"ip":{},"relative_base":{},"status":{},"memory":[{}],"far":[{}],"input":[{}],"output":[{}]}}"#,
+            VERSION,
+            self.ip,
+            self.relative_base,
+            status,
+            join(self.memory.as_slice()),
+            far.join(","),
+            join(&input),
+            join(&self.output),
+        )
+    }
+
+    pub fn from_json(json: &str) -> Result<Snapshot, SnapshotError> {
+        let mut parser = Parser { text: json.as_bytes(), pos: 0 };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.pos != json.len() {
+            return format_error("trailing characters after snapshot")
+        }
+        let fields = match value {
+            Value::Object(x) => x,
+            _                => { return format_error("expected an object") },
+        };
+        let field = |name: &str| match fields.iter().find(|(key, _)| key == name) {
+            Some((_, value)) => Ok(value),
+            None             => Err(SnapshotError::Format(format!("missing field {:?}", name))),
+        };
+
+        let version = field("version")?.number()?;
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version))
+        }
+        let status = match field("status")? {
+            Value::String(x) if x == "running"           => Status::Running,
+            Value::String(x) if x == "halted"            => Status::Halted,
+            Value::String(x) if x == "waiting_for_input" => Status::WaitingForInput,
+            Value::Object(x) if x.len() == 1 && x[0].0 == "output" => Status::Output(x[0].1.number()?),
+            _ => { return format_error("unknown status") },
+        };
+        let mut memory = Memory::from(&field("memory")?.numbers()?[..]);
+        for pair in field("far")?.array()? {
+            match &pair.numbers()?[..] {
+                [addr, value] => memory.set(to_usize(*addr)?, *value),
+                _             => { return format_error("expected an [address, value] pair") },
+            }
+        }
+
+        Ok(Snapshot {
+            memory,
+            ip: to_usize(field("ip")?.number()?)?,
+            relative_base: field("relative_base")?.number()?,
+            status,
+            input: field("input")?.numbers()?.into_iter().collect(),
+            output: field("output")?.numbers()?,
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SnapshotError> {
+        fs::write(path, self.to_json() + "\n")?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Snapshot, SnapshotError> {
+        Snapshot::from_json(fs::read_to_string(path)?.trim_end())
+    }
+}
+
+fn to_usize(value: Word) -> Result<usize, SnapshotError> {
+    match usize::try_from(value) {
+        Ok(x)  => Ok(x),
+        Err(_) => format_error("expected an address"),
+    }
+}
+
+// Just enough JSON to read back what to_json() writes: integers, strings
+// without escapes, arrays and objects
+enum Value {
+    Number(Word),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn number(&self) -> Result<Word, SnapshotError> {
+        match self {
+            Value::Number(x) => Ok(*x),
+            _                => format_error("expected a number"),
+        }
+    }
+
+    fn array(&self) -> Result<&[Value], SnapshotError> {
+        match self {
+            Value::Array(x) => Ok(x),
+            _               => format_error("expected an array"),
+        }
+    }
+
+    fn numbers(&self) -> Result<Vec<Word>, SnapshotError> {
+        self.array()?.iter().map(|x| x.number()).collect()
+    }
+}
+
+struct Parser<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error<T>(&self, expected: &str) -> Result<T, SnapshotError> {
+        Err(SnapshotError::Format(format!("expected {} at offset {}", expected, self.pos)))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.text.len() && self.text[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.text.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), SnapshotError> {
+        match self.peek() {
+            Some(x) if x == c => { self.pos += 1; Ok(()) },
+            _                 => self.error(&format!("{:?}", c as char)),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, SnapshotError> {
+        match self.peek() {
+            Some(b'"')                      => Ok(Value::String(self.string()?)),
+            Some(b'[')                      => {
+                let items = self.list(b']', |parser| parser.value())?;
+                Ok(Value::Array(items))
+            },
+            Some(b'{')                      => {
+                let fields = self.list(b'}', |parser| {
+                    let key = parser.string()?;
+                    parser.expect(b':')?;
+                    Ok((key, parser.value()?))
+                })?;
+                Ok(Value::Object(fields))
+            },
+            Some(x) if x == b'-' || x.is_ascii_digit() => self.number(),
+            _                               => self.error("a value"),
+        }
+    }
+
+    // A comma separated list of items, after the opening bracket
+    fn list<T, F>(&mut self, close: u8, mut item: F) -> Result<Vec<T>, SnapshotError>
+        where F: FnMut(&mut Self) -> Result<T, SnapshotError>
+    {
+        self.pos += 1;
+        let mut items = Vec::new();
+        if self.peek() == Some(close) {
+            self.pos += 1;
+            return Ok(items)
+        }
+        loop {
+            items.push(item(self)?);
+            match self.peek() {
+                Some(b',')           => { self.pos += 1 },
+                Some(x) if x == close => { self.pos += 1; return Ok(items) },
+                _ => { return self.error(&format!("',' or '{}'", close as char)) },
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, SnapshotError> {
+        self.expect(b'"')?;
+        let start = self.pos;
+        while self.pos < self.text.len() && self.text[self.pos] != b'"' {
+            self.pos += 1;
+        }
+        if self.pos == self.text.len() {
+            return self.error("'\"'")
+        }
+        self.pos += 1;
+        Ok(String::from_utf8_lossy(&self.text[start..self.pos - 1]).into_owned())
+    }
+
+    fn number(&mut self) -> Result<Value, SnapshotError> {
+        let start = self.pos;
+        if self.text[self.pos] == b'-' {
+            self.pos += 1;
+        }
+        while self.pos < self.text.len() && self.text[self.pos].is_ascii_digit() {
+            self.pos += 1;
+        }
+        match String::from_utf8_lossy(&self.text[start..self.pos]).parse() {
+            Ok(x)  => Ok(Value::Number(x)),
+            Err(_) => {
+                self.pos = start;
+                self.error("a number")
+            },
+        }
+    }
+}
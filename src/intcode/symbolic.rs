@@ -0,0 +1,551 @@
+// Symbolic execution of Intcode programs. Chosen memory cells and inputs are
+// named variables rather than numbers, and arithmetic on them builds up
+// expressions, so that what a program leaves in memory comes back as a
+// formula in terms of its inputs. Day 2, for example, leaves
+//   noun*202500 + verb + 655695
+// at address 0 (for the puzzle input), and Linear::solve() can then find the
+// noun and verb which give any result without running the program again.
+//
+// Control flow has to stay concrete: instructions, jump conditions and
+// targets, write addresses and the relative base can't depend on a variable.
+// Reads from an address which does are allowed, but the value read can only
+// be described as "whatever was at that address"
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use super::decode::Instruction;
+use super::get_opcode;
+use super::{IntcodeError, Opcode, ParameterMode, Word};
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum Expr {
+    Const(Word),
+    Var(String),
+    // The value at an address which isn't known until the program runs
+    Load(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Lt(Box<Expr>, Box<Expr>),
+    Eq(Box<Expr>, Box<Expr>),
+}
+
+#[test]
+fn test_display_expr() {
+    let expr = Expr::Add(
+        Box::new(Expr::Mul(Box::new(Expr::var("noun")), Box::new(Expr::Const(-3)))),
+        Box::new(Expr::Load(Box::new(Expr::var("verb")))),
+    );
+    assert_eq!(expr.to_string(), "((noun * -3) + [verb])");
+}
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Const(x)   => write!(f, "{}", x),
+            Expr::Var(name)  => write!(f, "{}", name),
+            Expr::Load(x)    => write!(f, "[{}]", x),
+            Expr::Add(a, b)  => write!(f, "({} + {})", a, b),
+            Expr::Mul(a, b)  => write!(f, "({} * {})", a, b),
+            Expr::Lt(a, b)   => write!(f, "({} < {})", a, b),
+            Expr::Eq(a, b)   => write!(f, "({} == {})", a, b),
+        }
+    }
+}
+
+#[test]
+fn test_linear() {
+    let var = |name: &str| Expr::Var(name.to_string());
+    // (x + 2) * 3 + (y + x)
+    let expr = Expr::Add(
+        Box::new(Expr::Mul(Box::new(Expr::Add(Box::new(var("x")), Box::new(Expr::Const(2)))), Box::new(Expr::Const(3)))),
+        Box::new(Expr::Add(Box::new(var("y")), Box::new(var("x")))),
+    );
+    let linear = expr.linear().unwrap();
+    assert_eq!(linear.constant, 6);
+    assert_eq!(linear.coefficients.get("x"), Some(&4));
+    assert_eq!(linear.to_string(), "x*4 + y + 6");
+
+    assert!(Expr::Mul(Box::new(var("x")), Box::new(var("y"))).linear().is_none());
+    assert!(Expr::Load(Box::new(var("x"))).linear().is_none());
+}
+impl Expr {
+    pub fn var(name: &str) -> Expr {
+        Expr::Var(name.to_string())
+    }
+
+    pub fn as_const(&self) -> Option<Word> {
+        match self {
+            Expr::Const(x) => Some(*x),
+            _              => None,
+        }
+    }
+
+    // The expression as a sum of variables times constants plus a constant,
+    // if it is one. None if it's not linear, or arithmetic on its constants
+    // overflows
+    pub fn linear(&self) -> Option<Linear> {
+        match self {
+            Expr::Const(x)  => Some(Linear { constant: *x, coefficients: BTreeMap::new() }),
+            Expr::Var(name) => {
+                let mut coefficients = BTreeMap::new();
+                coefficients.insert(name.clone(), 1);
+                Some(Linear { constant: 0, coefficients })
+            },
+            Expr::Add(a, b) => a.linear()?.add(&b.linear()?),
+            Expr::Mul(a, b) => {
+                let (a, b) = (a.linear()?, b.linear()?);
+                match (a.coefficients.is_empty(), b.coefficients.is_empty()) {
+                    (true, _) => b.scale(a.constant),
+                    (_, true) => a.scale(b.constant),
+                    _         => None,
+                }
+            },
+            _ => None,
+        }
+    }
+}
+
+// Builds a + b, folding constants. Fails on overflow, as the VM would
+fn add(a: Expr, b: Expr, overflow: &IntcodeError) -> Result<Expr, IntcodeError> {
+    match (a.as_const(), b.as_const()) {
+        (Some(x), Some(y)) => x.checked_add(y).map(Expr::Const).ok_or_else(|| overflow.clone()),
+        (Some(0), _)       => Ok(b),
+        (_, Some(0))       => Ok(a),
+        _                  => Ok(Expr::Add(Box::new(a), Box::new(b))),
+    }
+}
+
+fn mul(a: Expr, b: Expr, overflow: &IntcodeError) -> Result<Expr, IntcodeError> {
+    match (a.as_const(), b.as_const()) {
+        (Some(x), Some(y)) => x.checked_mul(y).map(Expr::Const).ok_or_else(|| overflow.clone()),
+        (Some(0), _)       => Ok(Expr::Const(0)),
+        (_, Some(0))       => Ok(Expr::Const(0)),
+        (Some(1), _)       => Ok(b),
+        (_, Some(1))       => Ok(a),
+        _                  => Ok(Expr::Mul(Box::new(a), Box::new(b))),
+    }
+}
+
+// constant + the sum of each variable times its coefficient
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Linear {
+    pub constant: Word,
+    pub coefficients: BTreeMap<String, Word>,
+}
+
+impl fmt::Display for Linear {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut first = true;
+        for (name, coefficient) in self.coefficients.iter() {
+            let sign = match (first, *coefficient < 0) {
+                (true, true)   => "-",
+                (true, false)  => "",
+                (false, true)  => " - ",
+                (false, false) => " + ",
+            };
+            match coefficient.unsigned_abs() {
+                1 => write!(f, "{}{}", sign, name)?,
+                x => write!(f, "{}{}*{}", sign, name, x)?,
+            }
+            first = false;
+        }
+        match (first, self.constant) {
+            (true, x)               => write!(f, "{}", x),
+            (false, 0)              => Ok(()),
+            (false, x) if x < 0     => write!(f, " - {}", -(x as i128)),
+            (false, x)              => write!(f, " + {}", x),
+        }
+    }
+}
+
+#[test]
+fn test_solve() {
+    // 3x + y + 10, over x in 0..=9 and y in 0..=5
+    let mut coefficients = BTreeMap::new();
+    coefficients.insert("x".to_string(), 3);
+    coefficients.insert("y".to_string(), 1);
+    let linear = Linear { constant: 10, coefficients };
+    assert_eq!(
+        linear.solve(20, &[("x", 0..=9), ("y", 0..=5)]),
+        Some(vec![vec![2, 4], vec![3, 1]])
+    );
+    assert_eq!(linear.solve(0, &[("x", 0..=9), ("y", 0..=5)]), Some(vec![]));
+    // Every variable needs a range
+    assert_eq!(linear.solve(20, &[("x", 0..=9)]), None);
+
+    // Variables which make no difference take every value in their range
+    let linear = Linear { constant: 1, coefficients: BTreeMap::new() };
+    assert_eq!(linear.solve(1, &[("x", 0..=1)]), Some(vec![vec![0], vec![1]]));
+
+    // Word::MIN / -1 doesn't fit in a Word, so there's no solution
+    let mut coefficients = BTreeMap::new();
+    coefficients.insert("x".to_string(), -1);
+    let linear = Linear { constant: 0, coefficients };
+    assert_eq!(linear.solve(Word::MIN, &[("x", 0..=9)]), Some(vec![]));
+    assert_eq!(linear.solve(-3, &[("x", 0..=9)]), Some(vec![vec![3]]));
+
+    // Coefficients as large as they get
+    let mut coefficients = BTreeMap::new();
+    coefficients.insert("x".to_string(), Word::MIN);
+    coefficients.insert("y".to_string(), Word::MAX);
+    let linear = Linear { constant: Word::MIN, coefficients };
+    assert_eq!(linear.to_string(), "-x*9223372036854775808 + y*9223372036854775807 - 9223372036854775808");
+    assert_eq!(linear.solve(Word::MIN, &[("x", 0..=1), ("y", 0..=1)]), Some(vec![vec![0, 0]]));
+}
+impl Linear {
+    fn add(&self, other: &Linear) -> Option<Linear> {
+        let mut sum = self.clone();
+        sum.constant = sum.constant.checked_add(other.constant)?;
+        for (name, coefficient) in other.coefficients.iter() {
+            let entry = sum.coefficients.entry(name.clone()).or_insert(0);
+            *entry = entry.checked_add(*coefficient)?;
+        }
+        sum.coefficients.retain(|_, x| *x != 0);
+        Some(sum)
+    }
+
+    fn scale(&self, factor: Word) -> Option<Linear> {
+        let mut product = Linear { constant: self.constant.checked_mul(factor)?, coefficients: BTreeMap::new() };
+        for (name, coefficient) in self.coefficients.iter() {
+            if factor != 0 {
+                product.coefficients.insert(name.clone(), coefficient.checked_mul(factor)?);
+            }
+        }
+        Some(product)
+    }
+
+    // The value of the expression for the given variables, or None if it
+    // overflows
+    fn value(&self, values: &HashMap<&str, Word>) -> Option<Word> {
+        let mut total = self.constant;
+        for (name, coefficient) in self.coefficients.iter() {
+            total = total.checked_add(coefficient.checked_mul(values[name.as_str()])?)?;
+        }
+        Some(total)
+    }
+
+    // Every assignment of the variables within their ranges which makes the
+    // expression equal target, each giving values in the same order as
+    // ranges, sorted. None if any variable in the expression has no range.
+    //
+    // One variable is solved for directly, while the rest are tried over
+    // their whole ranges, so the variable with the largest range should have
+    // a non-zero coefficient, and be given last
+    pub fn solve(&self, target: Word, ranges: &[(&str, RangeInclusive<Word>)]) -> Option<Vec<Vec<Word>>> {
+        if self.coefficients.keys().any(|x| ranges.iter().all(|(name, _)| name != x)) {
+            return None
+        }
+        let solved = ranges.iter().rposition(|(name, _)| self.coefficients.contains_key(*name));
+        let mut solutions = Vec::new();
+        let mut values = vec![0; ranges.len()];
+        self.search(target, ranges, solved, 0, &mut values, &mut solutions);
+        solutions.sort();
+        Some(solutions)
+    }
+
+    // Tries every value of the variable at index (other than the one being
+    // solved for), then moves on to the next
+    fn search(
+        &self,
+        target: Word,
+        ranges: &[(&str, RangeInclusive<Word>)],
+        solved: Option<usize>,
+        index: usize,
+        values: &mut Vec<Word>,
+        solutions: &mut Vec<Vec<Word>>,
+    ) {
+        if index == ranges.len() {
+            let assigned: HashMap<&str, Word> = ranges.iter().map(|(name, _)| *name).zip(values.iter().copied()).collect();
+            match solved {
+                Some(i) if self.solve_for(target, ranges, i, values, &assigned) => solutions.push(values.clone()),
+                None if self.value(&assigned) == Some(target) => solutions.push(values.clone()),
+                _ => {},
+            }
+            return
+        }
+        if solved == Some(index) {
+            return self.search(target, ranges, solved, index + 1, values, solutions)
+        }
+        for value in ranges[index].1.clone() {
+            values[index] = value;
+            self.search(target, ranges, solved, index + 1, values, solutions);
+        }
+    }
+
+    // Works out the value of the variable at index from the others, if there
+    // is one within its range, and stores it in values
+    fn solve_for(
+        &self,
+        target: Word,
+        ranges: &[(&str, RangeInclusive<Word>)],
+        index: usize,
+        values: &mut [Word],
+        assigned: &HashMap<&str, Word>,
+    ) -> bool {
+        let (name, range) = &ranges[index];
+        let coefficient = self.coefficients[*name];
+        let mut rest = assigned.clone();
+        rest.insert(*name, 0);
+        let remainder = match self.value(&rest).and_then(|x| target.checked_sub(x)) {
+            Some(x) => x,
+            None    => { return false },
+        };
+        // Neither can fail unless remainder is Word::MIN and coefficient -1,
+        // when the answer is out of range anyway
+        match (remainder.checked_rem(coefficient), remainder.checked_div(coefficient)) {
+            (Some(0), Some(x)) if range.contains(&x) => {
+                values[index] = x;
+                true
+            },
+            _ => false,
+        }
+    }
+}
+
+// Everything that can stop symbolic execution
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum SymbolicError {
+    Intcode(IntcodeError),
+    // Something which has to be known exactly depends on a variable
+    Symbolic { ip: usize, what: &'static str, expr: Expr },
+}
+
+impl fmt::Display for SymbolicError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SymbolicError::Intcode(e) => write!(f, "{}", e),
+            SymbolicError::Symbolic { ip, what, expr } => {
+                write!(f, "{} at ip {} depends on a variable: {}", what, ip, expr)
+            },
+        }
+    }
+}
+
+impl Error for SymbolicError {}
+
+impl From<IntcodeError> for SymbolicError {
+    fn from(error: IntcodeError) -> SymbolicError {
+        SymbolicError::Intcode(error)
+    }
+}
+
+// A machine whose memory, input and output hold expressions
+pub struct Symbolic {
+    memory: Vec<Expr>,
+    far: HashMap<usize, Expr>,
+    ip: usize,
+    relative_base: Word,
+    input: VecDeque<Expr>,
+    output: Vec<Expr>,
+}
+
+#[test]
+fn test_symbolic() {
+    // Reads x into 17, outputs x * 2 + [19], then stores x == 5 at 17
+    let tape = [3,17,1002,17,2,18,1,18,19,18,4,18,1008,17,5,17,99,0,0,0];
+    // Cut short, with y past the end of memory
+    let mut machine = Symbolic::new(&tape[..12]);
+    machine.push_input(Expr::var("x"));
+    machine.set(19, Expr::var("y"));
+    assert_eq!(
+        machine.run(100),
+        Err(SymbolicError::Intcode(IntcodeError::InvalidOpcode { ip: 12, instruction: 0 }))
+    );
+    assert_eq!(machine.output()[0].linear().unwrap().to_string(), "x*2 + y");
+
+    let mut machine = Symbolic::new(&tape);
+    machine.push_input(Expr::var("x"));
+    assert_eq!(machine.run(100), Ok(()));
+    assert_eq!(machine.output(), &[Expr::Mul(Box::new(Expr::var("x")), Box::new(Expr::Const(2)))]);
+    assert_eq!(machine.get(17).to_string(), "(x == 5)");
+
+    // Jumping on a variable isn't possible
+    let mut machine = Symbolic::new(&[3,5,1005,5,0,0]);
+    machine.push_input(Expr::var("x"));
+    assert_eq!(
+        machine.run(100),
+        Err(SymbolicError::Symbolic { ip: 2, what: "jump condition", expr: Expr::var("x") })
+    );
+
+    // Nor is waiting for ever on a program which never halts
+    let mut machine = Symbolic::new(&[1105,1,0]);
+    assert_eq!(
+        machine.run(100),
+        Err(SymbolicError::Intcode(IntcodeError::StepLimit { ip: 0, instruction: 1105, steps: 100 }))
+    );
+}
+impl Symbolic {
+    pub fn new(tape: &[Word]) -> Symbolic {
+        Symbolic {
+            memory: tape.iter().map(|x| Expr::Const(*x)).collect(),
+            far: HashMap::new(),
+            ip: 0,
+            relative_base: 0,
+            input: VecDeque::new(),
+            output: Vec::new(),
+        }
+    }
+
+    pub fn get(&self, address: usize) -> Expr {
+        match self.memory.get(address) {
+            Some(x) => x.clone(),
+            None    => self.far.get(&address).cloned().unwrap_or(Expr::Const(0)),
+        }
+    }
+
+    // Sets a cell, e.g. to a variable before running
+    pub fn set(&mut self, address: usize, value: Expr) {
+        match self.memory.get_mut(address) {
+            Some(x) => *x = value,
+            None    => { self.far.insert(address, value); },
+        }
+    }
+
+    pub fn push_input(&mut self, value: Expr) {
+        self.input.push_back(value);
+    }
+
+    pub fn output(&self) -> &[Expr] {
+        &self.output
+    }
+
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    fn concrete(&self, expr: Expr, what: &'static str) -> Result<Word, SymbolicError> {
+        match expr {
+            Expr::Const(x) => Ok(x),
+            expr           => Err(SymbolicError::Symbolic { ip: self.ip, what, expr }),
+        }
+    }
+
+    fn address(&self, value: Word, instruction: Word) -> Result<usize, IntcodeError> {
+        super::to_address(value, self.ip, instruction)
+    }
+
+    // Runs until the program halts, for at most max_steps instructions.
+    // Stops with an error (leaving ip at the instruction responsible) on
+    // anything the VM would fail on, including running out of steps, or which
+    // can't be done without knowing a variable's value
+    pub fn run(&mut self, max_steps: usize) -> Result<(), SymbolicError> {
+        let mut steps = 0;
+        loop {
+            let ip = self.ip;
+            let raw = self.concrete(self.get(ip), "instruction")?;
+            if steps == max_steps {
+                return Err(IntcodeError::StepLimit { ip, instruction: raw, steps }.into())
+            }
+            steps += 1;
+            let opcode = get_opcode(&raw).ok_or(IntcodeError::InvalidOpcode { ip, instruction: raw })?;
+            if opcode == Opcode::Stop {
+                return Ok(())
+            }
+            let info = opcode.info();
+            let decoded = Instruction::new(raw, ip, info.num_args, info.dest())?;
+            let overflow = IntcodeError::Overflow { ip, instruction: raw };
+
+            // Each argument's value, or for a write destination, its address
+            let mut args = Vec::with_capacity(decoded.num_args);
+            let mut dest = None;
+            for i in 0..decoded.num_args {
+                let operand = self.get(ip + i + 1);
+                let address = match decoded.modes[i] {
+                    ParameterMode::Immediate => {
+                        args.push(operand);
+                        continue
+                    },
+                    ParameterMode::Position  => operand,
+                    ParameterMode::Relative  => add(Expr::Const(self.relative_base), operand, &overflow)?,
+                };
+                if decoded.dest == Some(i) {
+                    let address = self.concrete(address, "write address")?;
+                    dest = Some(self.address(address, raw)?);
+                    continue
+                }
+                args.push(match address {
+                    Expr::Const(x) => self.get(self.address(x, raw)?),
+                    address        => Expr::Load(Box::new(address)),
+                });
+            }
+
+            let mut next = ip + decoded.size();
+            let value = match opcode {
+                Opcode::Add => Some(add(args[0].clone(), args[1].clone(), &overflow)?),
+                Opcode::Mul => Some(mul(args[0].clone(), args[1].clone(), &overflow)?),
+                Opcode::Lt | Opcode::Eq => {
+                    let (a, b) = (args[0].clone(), args[1].clone());
+                    Some(match (a.as_const(), b.as_const(), opcode) {
+                        (Some(x), Some(y), Opcode::Lt) => Expr::Const(Word::from(x < y)),
+                        (Some(x), Some(y), _)          => Expr::Const(Word::from(x == y)),
+                        (_, _, Opcode::Lt)             => Expr::Lt(Box::new(a), Box::new(b)),
+                        _                              => Expr::Eq(Box::new(a), Box::new(b)),
+                    })
+                },
+                Opcode::Mov => {
+                    match self.input.pop_front() {
+                        Some(x) => Some(x),
+                        None    => { return Err(IntcodeError::InputExhausted { ip, instruction: raw }.into()) },
+                    }
+                },
+                Opcode::Out => {
+                    self.output.push(args[0].clone());
+                    None
+                },
+                Opcode::Jnz | Opcode::Jz => {
+                    let condition = self.concrete(args[0].clone(), "jump condition")?;
+                    if (condition != 0) == (opcode == Opcode::Jnz) {
+                        let target = self.concrete(args[1].clone(), "jump target")?;
+                        next = self.address(target, raw)?;
+                    }
+                    None
+                },
+                Opcode::AdjustRelativeBase => {
+                    let offset = self.concrete(args[0].clone(), "relative base")?;
+                    self.relative_base = self.relative_base.checked_add(offset).ok_or(overflow)?;
+                    None
+                },
+                Opcode::Stop => unreachable!(),
+            };
+            if let (Some(address), Some(value)) = (dest, value) {
+                self.set(address, value);
+            }
+            self.ip = next;
+        }
+    }
+}
+
+#[test]
+fn test_day2() {
+    let tape: Vec<Word> = crate::input_to_vec_t_fromstr(include_str!("../bin/day2/input"), ',');
+    let formula = solve_for_address(&tape, &[(1, "noun"), (2, "verb")], 0, 1000).unwrap();
+    assert_eq!(formula.to_string(), "noun*202500 + verb + 655695");
+    let ranges = [("noun", 0..=99), ("verb", 0..=99)];
+    assert_eq!(formula.solve(19690720, &ranges), Some(vec![vec![94, 25]]));
+    assert_eq!(formula.solve(3085697, &ranges), Some(vec![vec![12, 2]]));
+}
+// Runs a program with the given cells replaced by named variables, and
+// returns what it leaves at result as a linear formula in those variables.
+// Fails if the program can't be run symbolically within max_steps
+// instructions, or the result isn't linear
+pub fn solve_for_address(
+    tape: &[Word],
+    variables: &[(usize, &str)],
+    result: usize,
+    max_steps: usize,
+) -> Result<Linear, SymbolicError> {
+    let mut machine = Symbolic::new(tape);
+    for (address, name) in variables.iter() {
+        machine.set(*address, Expr::var(name));
+    }
+    machine.run(max_steps)?;
+    let expr = machine.get(result);
+    expr.linear().ok_or(SymbolicError::Symbolic { ip: machine.ip, what: "result", expr })
+}
@@ -0,0 +1,357 @@
+// Runs Intcode machines on their own threads, with input and output as
+// std::sync::mpsc channels, so a machine can be plugged into any producer or
+// consumer without writing a scheduler by hand.
+//
+// spawn() runs a single machine against a pair of plain channels. Cluster
+// runs a group of machines which talk to each other, and can tell when they
+// have all halted or are stuck waiting on each other for input
+use std::sync::mpsc::{Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::thread::JoinHandle;
+
+use super::{IntcodeError, Machine, Status, Word};
+
+// Runs a machine until it halts, calling recv whenever it needs input and
+// send (if given) with each output. Stops early (leaving the machine waiting
+// for input) if recv returns None.
+//
+// Output which is sent on doesn't stay in the machine's own output
+fn drive<R, S>(machine: &mut Machine, mut recv: R, mut send: Option<S>) -> Result<(), IntcodeError>
+    where
+        R: FnMut() -> Option<Word>,
+        S: FnMut(Word),
+{
+    loop {
+        match machine.run_until_output()? {
+            Status::Output(x)       => {
+                if let Some(ref mut send) = send {
+                    machine.take_output();
+                    send(x);
+                }
+            },
+            Status::WaitingForInput => {
+                match recv() {
+                    Some(x) => machine.push_input(x),
+                    None    => { return Ok(()) },
+                }
+            },
+            _ => { return Ok(()) },
+        }
+    }
+}
+
+#[test]
+fn test_spawn() {
+    use std::sync::mpsc::channel;
+
+    let (input, rx) = channel();
+    let (tx, output) = channel();
+    let handle = spawn(Machine::new(&super::fixtures::ECHO), rx, tx);
+
+    input.send(4).unwrap();
+    assert_eq!(output.recv(), Ok(8));
+    input.send(21).unwrap();
+    input.send(0).unwrap();
+    assert_eq!(output.recv(), Ok(42));
+    // The output channel closes once the machine halts
+    assert!(output.recv().is_err());
+    assert_eq!(handle.join().unwrap().unwrap().status(), Status::Halted);
+}
+// Runs a machine on a new thread, reading input from one channel and sending
+// output to another. The output channel is closed once the machine halts.
+//
+// If the input channel is closed while the machine is waiting for input, the
+// thread finishes and hands back the machine, still waiting for input
+pub fn spawn(mut machine: Machine, input: Receiver<Word>, output: Sender<Word>) -> JoinHandle<Result<Machine, IntcodeError>> {
+    thread::spawn(move || {
+        // If the other end has hung up there's nobody to send to, but the
+        // machine may still have side effects worth finishing, so carry on
+        drive(&mut machine, || input.recv().ok(), Some(|x| { output.send(x).ok(); }))?;
+        Ok(machine)
+    })
+}
+
+// Bookkeeping shared by every thread in a cluster, used to spot deadlock.
+// Values sent between machines are always sent while holding the lock, so
+// the count of values in flight is exact
+struct State {
+    // Machines whose threads have not finished yet
+    live: usize,
+    // Machines blocked waiting for input
+    blocked: usize,
+    // Values sent to a machine but not yet read by it
+    in_flight: usize,
+    finished: Vec<bool>,
+    deadlocked: bool,
+}
+
+impl State {
+    fn check_deadlock(&mut self) {
+        if self.live > 0 && self.blocked == self.live && self.in_flight == 0 {
+            self.deadlocked = true;
+        }
+    }
+}
+
+struct Monitor {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+#[derive(Clone)]
+enum Destination {
+    Machine(usize, Sender<Word>),
+    External(Sender<Word>),
+}
+
+impl Monitor {
+    fn send(&self, destination: &Destination, value: Word) {
+        match destination {
+            Destination::Machine(index, tx) => {
+                let mut state = self.state.lock().unwrap();
+                if !state.finished[*index] && tx.send(value).is_ok() {
+                    state.in_flight += 1;
+                    self.changed.notify_all();
+                }
+            },
+            Destination::External(tx) => { tx.send(value).ok(); },
+        }
+    }
+
+    // Blocks until a value arrives for this machine, or every machine in the
+    // cluster is blocked and nothing is on its way, i.e. deadlock
+    fn recv(&self, rx: &Receiver<Word>) -> Option<Word> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            match rx.try_recv() {
+                Ok(x) => {
+                    state.in_flight -= 1;
+                    return Some(x)
+                },
+                Err(TryRecvError::Disconnected) => { return None },
+                Err(TryRecvError::Empty)        => {},
+            }
+            if state.deadlocked {
+                return None
+            }
+            state.blocked += 1;
+            state.check_deadlock();
+            if state.deadlocked {
+                state.blocked -= 1;
+                self.changed.notify_all();
+                return None
+            }
+            state = self.changed.wait(state).unwrap();
+            state.blocked -= 1;
+        }
+    }
+
+    // Marks a machine's thread as finished, discarding anything still
+    // waiting to be read by it
+    fn finish(&self, index: usize, rx: &Receiver<Word>) {
+        let mut state = self.state.lock().unwrap();
+        state.finished[index] = true;
+        state.live -= 1;
+        while rx.try_recv().is_ok() {
+            state.in_flight -= 1;
+        }
+        state.check_deadlock();
+        self.changed.notify_all();
+    }
+}
+
+// Sends input into a cluster from outside, e.g. to get things started
+#[derive(Clone)]
+pub struct Injector {
+    monitor: Arc<Monitor>,
+    destination: Destination,
+}
+
+impl Injector {
+    pub fn send(&self, value: Word) {
+        self.monitor.send(&self.destination, value);
+    }
+}
+
+// How a cluster finished running
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum ClusterStatus {
+    // Every machine halted
+    Halted,
+    // Every machine still running was waiting for input with none on the
+    // way. Those machines are handed back still waiting for input
+    Deadlocked,
+}
+
+// A group of machines, each run on its own thread, with outputs wired to
+// inputs through channels.
+//
+// Input sent from outside the cluster must go through an Injector, and must
+// be sent before the cluster runs into deadlock, since the cluster can't
+// know about input which might arrive later
+pub struct Cluster {
+    machines: Vec<Machine>,
+    receivers: Vec<Receiver<Word>>,
+    senders: Vec<Sender<Word>>,
+    destinations: Vec<Vec<Destination>>,
+    monitor: Arc<Monitor>,
+}
+
+impl Default for Cluster {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cluster {
+    pub fn new() -> Cluster {
+        Cluster {
+            machines: Vec::new(),
+            receivers: Vec::new(),
+            senders: Vec::new(),
+            destinations: Vec::new(),
+            monitor: Arc::new(Monitor {
+                state: Mutex::new(State {
+                    live: 0,
+                    blocked: 0,
+                    in_flight: 0,
+                    finished: Vec::new(),
+                    deadlocked: false,
+                }),
+                changed: Condvar::new(),
+            }),
+        }
+    }
+
+    // Adds a machine to the cluster, returning its index
+    pub fn add(&mut self, machine: Machine) -> usize {
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.machines.push(machine);
+        self.receivers.push(rx);
+        self.senders.push(tx);
+        self.destinations.push(Vec::new());
+
+        let mut state = self.monitor.state.lock().unwrap();
+        state.live += 1;
+        state.finished.push(false);
+        self.machines.len() - 1
+    }
+
+    // Sends every output of one machine to the input of another
+    pub fn connect(&mut self, from: usize, to: usize) {
+        let destination = Destination::Machine(to, self.senders[to].clone());
+        self.destinations[from].push(destination);
+    }
+
+    // Sends every output of a machine out of the cluster, to any consumer
+    pub fn connect_external(&mut self, from: usize, tx: Sender<Word>) {
+        self.destinations[from].push(Destination::External(tx));
+    }
+
+    pub fn injector(&self, to: usize) -> Injector {
+        Injector {
+            monitor: self.monitor.clone(),
+            destination: Destination::Machine(to, self.senders[to].clone()),
+        }
+    }
+
+    // Runs every machine on its own thread until they have all halted or
+    // deadlocked, and hands back the machines. Output from machines with no
+    // outgoing connections is left in each machine's output.
+    //
+    // If any machine fails, the first error is returned once every other
+    // machine has finished
+    pub fn run(self) -> Result<(Vec<Machine>, ClusterStatus), IntcodeError> {
+        // Only the machines' own connections should keep channels open
+        drop(self.senders);
+
+        let mut handles = Vec::new();
+        let parts = self.machines.into_iter()
+            .zip(self.receivers)
+            .zip(self.destinations);
+        for (index, ((mut machine, rx), destinations)) in parts.enumerate() {
+            let monitor = self.monitor.clone();
+            handles.push(thread::spawn(move || {
+                let send = |x| {
+                    for destination in destinations.iter() {
+                        monitor.send(destination, x);
+                    }
+                };
+                // With nowhere to send output, it stays in the machine
+                let send = if destinations.is_empty() { None } else { Some(send) };
+                let result = drive(&mut machine, || monitor.recv(&rx), send);
+                monitor.finish(index, &rx);
+                result.map(|_| machine)
+            }));
+        }
+
+        let mut machines = Vec::new();
+        let mut error = None;
+        for handle in handles {
+            match handle.join().unwrap() {
+                Ok(x)  => machines.push(x),
+                Err(e) => { error.get_or_insert(e); },
+            }
+        }
+        if let Some(e) = error {
+            return Err(e)
+        }
+        let status = match machines.iter().all(|x| x.status() == Status::Halted) {
+            true  => ClusterStatus::Halted,
+            false => ClusterStatus::Deadlocked,
+        };
+        Ok((machines, status))
+    }
+}
+
+#[test]
+fn test_cluster() {
+    // Day 7's feedback loop example
+    let tape = [3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5];
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut cluster = Cluster::new();
+    for phase in [9,8,7,6,5].iter() {
+        let mut machine = Machine::new(&tape);
+        machine.push_input(*phase);
+        cluster.add(machine);
+    }
+    for i in 0..4 {
+        cluster.connect(i, i + 1);
+    }
+    cluster.connect(4, 0);
+    cluster.connect_external(4, tx);
+    cluster.injector(0).send(0);
+
+    let (machines, status) = cluster.run().unwrap();
+    assert_eq!(status, ClusterStatus::Halted);
+    assert!(machines.iter().all(|x| x.status() == Status::Halted));
+    assert_eq!(rx.iter().last(), Some(139629729));
+}
+
+#[test]
+fn test_cluster_deadlock() {
+    // Two machines which each wait for the other to speak first
+    let echo = [3,7,4,7,1105,1,0,0];
+    let mut cluster = Cluster::new();
+    let a = cluster.add(Machine::new(&echo));
+    let b = cluster.add(Machine::new(&echo));
+    cluster.connect(a, b);
+    cluster.connect(b, a);
+    let (machines, status) = cluster.run().unwrap();
+    assert_eq!(status, ClusterStatus::Deadlocked);
+    assert!(machines.iter().all(|x| x.status() == Status::WaitingForInput));
+
+    // A pipeline where the last machine wants more input than it gets
+    let mut cluster = Cluster::new();
+    let a = cluster.add(Machine::new(&[3,0,4,0,99]));
+    let b = cluster.add(Machine::new(&[3,0,3,0,99]));
+    cluster.connect(a, b);
+    cluster.injector(a).send(1);
+    let (machines, status) = cluster.run().unwrap();
+    assert_eq!(status, ClusterStatus::Deadlocked);
+    assert_eq!(machines[0].status(), Status::Halted);
+    assert_eq!(machines[1].status(), Status::WaitingForInput);
+}
@@ -0,0 +1,223 @@
+// Execution tracing for Intcode machines. Each instruction executed while
+// tracing is described by a TraceEvent, which is handed to a TraceSink to
+// print, store or write out as it sees fit
+use std::fmt;
+use std::io;
+use std::io::Write;
+
+use super::{Opcode, Word};
+
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct TraceEvent {
+    pub ip: usize,
+    // Raw value of the instruction, including mode digits
+    pub instruction: Word,
+    // The standard opcode, unless the machine ran something else from its
+    // instruction set
+    pub opcode: Option<Opcode>,
+    // Name of the instruction, from the instruction set which ran it
+    pub mnemonic: String,
+    // Arguments after resolving parameter modes. As in the VM itself, a
+    // write destination is given as the address written to
+    pub args: Vec<Word>,
+    // Every address read for an argument
+    pub reads: Vec<usize>,
+    // Every (address, value) pair written to memory
+    pub writes: Vec<(usize, Word)>,
+    pub input: Option<Word>,
+    pub output: Option<Word>,
+}
+
+#[test]
+fn test_display_event() {
+    let event = TraceEvent {
+        ip: 2,
+        instruction: 8,
+        opcode: Some(Opcode::Eq),
+        mnemonic: "EQ".to_string(),
+        args: vec![8, 8, 9],
+        reads: vec![],
+        writes: vec![(9, 1)],
+        input: None,
+        output: None,
+    };
+    assert_eq!(format!("{}", event), "0002: EQ 8, 8, 9 ; [9] = 1");
+
+    let event = TraceEvent {
+        ip: 6,
+        instruction: 4,
+        opcode: Some(Opcode::Out),
+        mnemonic: "OUT".to_string(),
+        args: vec![1],
+        reads: vec![],
+        writes: vec![],
+        input: None,
+        output: Some(1),
+    };
+    assert_eq!(format!("{}", event), "0006: OUT 1 ; output 1");
+}
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}: {}", self.ip, self.mnemonic)?;
+        for (i, arg) in self.args.iter().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+            write!(f, "{}{}", separator, arg)?;
+        }
+        if let Some(x) = self.input {
+            write!(f, " ; input {}", x)?;
+        }
+        for (addr, value) in self.writes.iter() {
+            write!(f, " ; [{}] = {}", addr, value)?;
+        }
+        if let Some(x) = self.output {
+            write!(f, " ; output {}", x)?;
+        }
+        Ok(())
+    }
+}
+
+fn json_option(value: Option<Word>) -> String {
+    match value {
+        Some(x) => x.to_string(),
+        None    => "null".to_string(),
+    }
+}
+
+#[test]
+fn test_to_json() {
+    let event = TraceEvent {
+        ip: 0,
+        instruction: 3,
+        opcode: Some(Opcode::Mov),
+        mnemonic: "MOV".to_string(),
+        args: vec![9],
+        reads: vec![],
+        writes: vec![(9, 8)],
+        input: Some(8),
+        output: None,
+    };
+    assert_eq!(
+        event.to_json(),
+        r#"{"ip":0,"instruction":3,"opcode":"MOV","args":[9],"reads":[],"writes":[[9,8]],"input":8,"output":null}"#
+    );
+
+    let event = TraceEvent {
+        ip: 4,
+        instruction: 1001,
+        opcode: Some(Opcode::Add),
+        mnemonic: "ADD".to_string(),
+        args: vec![5, 1, 9],
+        reads: vec![7],
+        writes: vec![(9, 6)],
+        input: None,
+        output: None,
+    };
+    assert_eq!(
+        event.to_json(),
+        r#"{"ip":4,"instruction":1001,"opcode":"ADD","args":[5,1,9],"reads":[7],"writes":[[9,6]],"input":null,"output":null}"#
+    );
+}
+impl TraceEvent {
+    // Formats the event as a single line of JSON
+    pub fn to_json(&self) -> String {
+        let args: Vec<String> = self.args.iter().map(|x| x.to_string()).collect();
+        let reads: Vec<String> = self.reads.iter().map(|x| x.to_string()).collect();
+        let writes: Vec<String> = self.writes.iter()
+            .map(|(addr, value)| format!("[{},{}]", addr, value))
+            .collect();
+        format!(
+            r#"{{"ip":{},"instruction":{},"opcode":{:?},"args":[{}],"reads":[{}],"writes":[{}],"input":{},"output":{}}}"#,
+            self.ip,
+            self.instruction,
+            self.mnemonic,
+            args.join(","),
+            reads.join(","),
+            writes.join(","),
+            json_option(self.input),
+            json_option(self.output),
+        )
+    }
+}
+
+// Somewhere to send trace events as a machine runs
+pub trait TraceSink {
+    fn record(&mut self, event: &TraceEvent);
+}
+
+// Collects every event in memory
+impl TraceSink for Vec<TraceEvent> {
+    fn record(&mut self, event: &TraceEvent) {
+        self.push(event.clone());
+    }
+}
+
+// Prints each event to stderr, one instruction per line
+pub struct Stderr;
+
+impl TraceSink for Stderr {
+    fn record(&mut self, event: &TraceEvent) {
+        eprintln!("{}", event);
+    }
+}
+
+// Writes each event as a line of JSON, e.g. to a file. A failed write
+// mustn't stop the machine being traced, so the first error is kept (and
+// nothing more written) until flush() or finish() reports it
+pub struct JsonLines<W: Write> {
+    writer: W,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> JsonLines<W> {
+    pub fn new(writer: W) -> JsonLines<W> {
+        JsonLines { writer, error: None }
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        if let Some(e) = self.error.take() {
+            return Err(e)
+        }
+        self.writer.flush()
+    }
+
+    // Flushes what has been written, returning the writer if every event
+    // was written out
+    pub fn finish(mut self) -> io::Result<W> {
+        self.flush()?;
+        Ok(self.writer)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[test]
+fn test_json_lines() {
+    let mut sink = JsonLines::new(Vec::new());
+    super::run_program_traced(&[104,7,99], &[], &mut sink).unwrap();
+    let written = String::from_utf8(sink.into_inner()).unwrap();
+    assert_eq!(
+        written.lines().collect::<Vec<&str>>(),
+        vec![
+            r#"{"ip":0,"instruction":104,"opcode":"OUT","args":[7],"reads":[],"writes":[],"input":null,"output":7}"#,
+            r#"{"ip":2,"instruction":99,"opcode":"STOP","args":[],"reads":[],"writes":[],"input":null,"output":null}"#,
+        ]
+    );
+
+    // Accepts one line, then fails
+    use super::fixtures::Full;
+    let mut sink = JsonLines::new(Full(1));
+    assert_eq!(super::run_program_traced(&[104,7,104,8,99], &[], &mut sink), Ok(vec![7, 8]));
+    assert_eq!(sink.finish().unwrap_err().to_string(), "disk full");
+    assert!(JsonLines::new(Full(5)).finish().is_ok());
+}
+impl<W: Write> TraceSink for JsonLines<W> {
+    fn record(&mut self, event: &TraceEvent) {
+        if self.error.is_none() {
+            self.error = writeln!(self.writer, "{}", event.to_json()).err();
+        }
+    }
+}
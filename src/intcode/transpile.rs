@@ -0,0 +1,409 @@
+// Translates an Intcode tape into Rust source, so a heavy program can be
+// compiled ahead of time and run natively.
+//
+// The generated code is a `match ip` state machine with one arm per basic
+// block: a straight run of instructions starting at address 0, a jump target,
+// or just after a jump. Anything it can't handle natively is handed over to
+// the interpreter, which carries on until it reaches the start of a block
+// which can be run natively again:
+//   - jumps to an address which doesn't start a block
+//   - values which don't decode as instructions
+//   - blocks whose code doesn't match the tape it was generated from, whether
+//     the program has written over them or they were patched before the run
+//   - everything, on a machine with its own instruction set, since generated
+//     code only knows how the standard instructions behave
+//
+// Programs which modify themselves in predictable ways (such as day 5, which
+// adds its input to the instruction at address 6) can be transpiled from the
+// tape as it will be once modified, using intcode-transpile's patches.
+//
+// Generated code reads and writes memory without recording which addresses
+// were touched, and always uses the machine's own input and output queues
+use std::fmt::Write;
+
+use super::analysis;
+use super::analysis::{Exit, Target};
+use super::disasm;
+use super::disasm::{Line, Operand};
+use super::{to_address, IntcodeError, Machine, Opcode, ParameterMode, Status, Word};
+
+// A basic block as it was on the tape the code was generated from
+pub struct Block {
+    pub start: usize,
+    pub code: &'static [Word],
+}
+
+// The state generated code runs against: the machine it stands in for, plus
+// which blocks currently differ from the tape and can't be run natively
+pub struct Runtime<'a> {
+    machine: &'a mut Machine,
+    ip: usize,
+    // Whether the machine runs the standard instructions, so that generated
+    // code can stand in for it at all
+    native: bool,
+    // For each address, the blocks covering it and the value each expects
+    cells: Vec<Vec<(usize, Word)>>,
+    // The block starting at each address, if any
+    starts: Vec<Option<usize>>,
+    // Number of addresses in each block whose value differs from the tape
+    mismatches: Vec<usize>,
+}
+
+impl<'a> Runtime<'a> {
+    pub fn new(machine: &'a mut Machine, blocks: &[Block]) -> Runtime<'a> {
+        let end = blocks.iter().map(|x| x.start + x.code.len() + 1).max().unwrap_or(0);
+        let mut cells = vec![Vec::new(); end];
+        let mut starts = vec![None; end];
+        let mut mismatches = vec![0; blocks.len()];
+        for (i, block) in blocks.iter().enumerate() {
+            starts[block.start] = Some(i);
+            for (offset, value) in block.code.iter().enumerate() {
+                let address = block.start + offset;
+                cells[address].push((i, *value));
+                if machine.memory.get(address) != *value {
+                    mismatches[i] += 1;
+                }
+            }
+        }
+        let ip = machine.ip;
+        let native = machine.instruction_set().is_none();
+        Runtime { machine, ip, native, cells, starts, mismatches }
+    }
+
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    pub fn jump(&mut self, ip: usize) {
+        self.ip = ip;
+    }
+
+    pub fn halted(&self) -> bool {
+        self.machine.status == Status::Halted
+    }
+
+    // Whether a block can't be run natively, because its code differs from
+    // the tape it was generated from or the machine has its own instructions
+    pub fn invalid(&self, block: usize) -> bool {
+        !self.native || self.mismatches[block] > 0
+    }
+
+    // Fails on behalf of the instruction at ip, leaving the machine there
+    fn fail<T>(&mut self, error: IntcodeError) -> Result<T, IntcodeError> {
+        self.machine.ip = error.ip();
+        Err(error)
+    }
+
+    pub fn address(&mut self, value: Word, ip: usize, instruction: Word) -> Result<usize, IntcodeError> {
+        match to_address(value, ip, instruction) {
+            Ok(x)  => Ok(x),
+            Err(e) => self.fail(e),
+        }
+    }
+
+    // The address an operand in relative mode refers to
+    pub fn relative(&mut self, offset: Word, ip: usize, instruction: Word) -> Result<Word, IntcodeError> {
+        match self.machine.relative_base.checked_add(offset) {
+            Some(x) => Ok(x),
+            None    => self.fail(IntcodeError::Overflow { ip, instruction }),
+        }
+    }
+
+    pub fn load(&mut self, address: Word, ip: usize, instruction: Word) -> Result<Word, IntcodeError> {
+        let address = self.address(address, ip, instruction)?;
+        Ok(self.machine.memory.get(address))
+    }
+
+    pub fn store(&mut self, address: usize, value: Word) {
+        let old = self.machine.memory.get(address);
+        self.machine.memory.set(address, value);
+        self.machine.decoded.invalidate(address);
+        self.changed(address, old, value);
+    }
+
+    // Keeps track of which blocks match the tape after a write
+    fn changed(&mut self, address: usize, old: Word, new: Word) {
+        if let Some(cells) = self.cells.get(address) {
+            for (block, expected) in cells.iter() {
+                match (old == *expected, new == *expected) {
+                    (true, false) => self.mismatches[*block] += 1,
+                    (false, true) => self.mismatches[*block] -= 1,
+                    _             => {},
+                }
+            }
+        }
+    }
+
+    pub fn add(&mut self, a: Word, b: Word, ip: usize, instruction: Word) -> Result<Word, IntcodeError> {
+        match a.checked_add(b) {
+            Some(x) => Ok(x),
+            None    => self.fail(IntcodeError::Overflow { ip, instruction }),
+        }
+    }
+
+    pub fn mul(&mut self, a: Word, b: Word, ip: usize, instruction: Word) -> Result<Word, IntcodeError> {
+        match a.checked_mul(b) {
+            Some(x) => Ok(x),
+            None    => self.fail(IntcodeError::Overflow { ip, instruction }),
+        }
+    }
+
+    pub fn adjust_relative_base(&mut self, value: Word, ip: usize, instruction: Word) -> Result<(), IntcodeError> {
+        let relative_base = self.relative(value, ip, instruction)?;
+        self.machine.relative_base = relative_base;
+        Ok(())
+    }
+
+    pub fn input(&mut self) -> Option<Word> {
+        self.machine.io.input.pop_front()
+    }
+
+    pub fn output(&mut self, value: Word) {
+        self.machine.io.output.push(value);
+    }
+
+    // Stops at the instruction at ip, which needs input
+    pub fn wait(&mut self, ip: usize) -> Status {
+        self.machine.ip = ip;
+        self.machine.status = Status::WaitingForInput;
+        self.machine.status
+    }
+
+    pub fn halt(&mut self, ip: usize) -> Status {
+        self.machine.ip = ip;
+        self.machine.status = Status::Halted;
+        self.machine.status
+    }
+
+    // Hands the machine over to the interpreter from ip onwards, until it
+    // stops or gets to a block which can be run natively. Returns the status
+    // if it stopped
+    pub fn interpret(&mut self, ip: usize) -> Result<Option<Status>, IntcodeError> {
+        self.machine.ip = ip;
+        loop {
+            // The interpreter writes to memory behind our back, so find out
+            // where it's going to write
+            let ip = self.machine.ip;
+            let dest = match self.machine.decode_next() {
+                Ok(x)  => x.dest(ip, self.machine.relative_base).unwrap_or(None),
+                Err(_) => None,
+            };
+            let old = dest.map(|x| self.machine.memory.get(x));
+            let status = self.machine.step()?;
+            if let (Some(address), Some(old)) = (dest, old) {
+                self.changed(address, old, self.machine.memory.get(address));
+            }
+
+            match status {
+                Status::Halted          => { return Ok(Some(Status::Halted)) },
+                Status::WaitingForInput => { return Ok(Some(Status::WaitingForInput)) },
+                _                       => {},
+            }
+            self.ip = self.machine.ip;
+            if let Some(Some(block)) = self.starts.get(self.ip) {
+                if !self.invalid(*block) {
+                    return Ok(None)
+                }
+            }
+        }
+    }
+}
+
+// Rust expression for the value of an operand which is read
+fn read_expr(operand: &Operand, ip: usize, raw: Word) -> String {
+    match operand.mode {
+        ParameterMode::Immediate => operand.value.to_string(),
+        ParameterMode::Position  => format!("rt.load({}, {}, {})?", operand.value, ip, raw),
+        ParameterMode::Relative  => {
+            let relative = format!("rt.relative({}, {}, {})?", operand.value, ip, raw);
+            format!("{{ let relative = {}; rt.load(relative, {}, {})? }}", relative, ip, raw)
+        },
+    }
+}
+
+// Rust expression for the address an operand writes to
+fn dest_expr(operand: &Operand, ip: usize, raw: Word) -> String {
+    match operand.mode {
+        ParameterMode::Relative => {
+            let relative = format!("rt.relative({}, {}, {})?", operand.value, ip, raw);
+            format!("{{ let relative = {}; rt.address(relative, {}, {})? }}", relative, ip, raw)
+        },
+        _ => format!("rt.address({}, {}, {})?", operand.value, ip, raw),
+    }
+}
+
+// Code to hand over to the interpreter at ip, then carry on natively if it
+// gets to a block which can be run
+fn interpret(code: &mut String, indent: &str, ip: usize) {
+    writeln!(code, "{}if let Some(status) = rt.interpret({})? {{", indent, ip).unwrap();
+    writeln!(code, "{}    return Ok(status)", indent).unwrap();
+    writeln!(code, "{}}}", indent).unwrap();
+    writeln!(code, "{}continue", indent).unwrap();
+}
+
+// Generates the body of one match arm, covering the given block
+fn block(tape: &[Word], span: &analysis::Block, index: usize) -> String {
+    let mut code = String::new();
+    let (start, end) = (span.start, span.end);
+    let mut address = start;
+    let indent = "                ";
+    if disasm::decode(tape, start).is_none() {
+        interpret(&mut code, indent, start);
+        return code
+    }
+    writeln!(code, "{}if rt.invalid({}) {{", indent, index).unwrap();
+    interpret(&mut code, &format!("{}    ", indent), start);
+    writeln!(code, "{}}}", indent).unwrap();
+
+    loop {
+        let line = match disasm::decode(tape, address) {
+            Some(x) => x,
+            None    => {
+                interpret(&mut code, indent, address);
+                return code
+            },
+        };
+        let (opcode, operands) = match &line {
+            Line::Instruction { opcode, operands, .. } => (*opcode, operands),
+            Line::Data { .. }                          => unreachable!(),
+        };
+        let ip = address;
+        let raw = tape[ip];
+        let next = ip + line.size();
+        let arg = |i: usize| read_expr(&operands[i], ip, raw);
+
+        writeln!(code, "{}// {}", indent, line).unwrap();
+        let mut stores = true;
+        match opcode {
+            Opcode::Add | Opcode::Mul | Opcode::Lt | Opcode::Eq => {
+                writeln!(code, "{}let a = {};", indent, arg(0)).unwrap();
+                writeln!(code, "{}let b = {};", indent, arg(1)).unwrap();
+                writeln!(code, "{}let address = {};", indent, dest_expr(&operands[2], ip, raw)).unwrap();
+                let value = match opcode {
+                    Opcode::Add => format!("rt.add(a, b, {}, {})?", ip, raw),
+                    Opcode::Mul => format!("rt.mul(a, b, {}, {})?", ip, raw),
+                    Opcode::Lt  => "Word::from(a < b)".to_string(),
+                    _           => "Word::from(a == b)".to_string(),
+                };
+                writeln!(code, "{}let value = {};", indent, value).unwrap();
+                writeln!(code, "{}rt.store(address, value);", indent).unwrap();
+            },
+            Opcode::Mov => {
+                writeln!(code, "{}let address = {};", indent, dest_expr(&operands[0], ip, raw)).unwrap();
+                writeln!(code, "{}match rt.input() {{", indent).unwrap();
+                writeln!(code, "{}    Some(x) => rt.store(address, x),", indent).unwrap();
+                writeln!(code, "{}    None    => {{ return Ok(rt.wait({})) }},", indent, ip).unwrap();
+                writeln!(code, "{}}}", indent).unwrap();
+            },
+            Opcode::Out => {
+                stores = false;
+                writeln!(code, "{}let a = {};", indent, arg(0)).unwrap();
+                writeln!(code, "{}rt.output(a);", indent).unwrap();
+            },
+            Opcode::Jnz | Opcode::Jz => {
+                writeln!(code, "{}let a = {};", indent, arg(0)).unwrap();
+                let target = analysis::jump_target(&operands[1]);
+                if target == Target::Computed {
+                    writeln!(code, "{}let b = {};", indent, arg(1)).unwrap();
+                }
+                let condition = if opcode == Opcode::Jnz { "a != 0" } else { "a == 0" };
+                writeln!(code, "{}if {} {{", indent, condition).unwrap();
+                match target {
+                    Target::Address(x) => writeln!(code, "{}    rt.jump({});", indent, x).unwrap(),
+                    Target::Computed   => {
+                        writeln!(code, "{}    let target = rt.address(b, {}, {})?;", indent, ip, raw).unwrap();
+                        writeln!(code, "{}    rt.jump(target);", indent).unwrap();
+                    },
+                }
+                writeln!(code, "{}    continue", indent).unwrap();
+                writeln!(code, "{}}}", indent).unwrap();
+                writeln!(code, "{}rt.jump({});", indent, next).unwrap();
+                return code
+            },
+            Opcode::AdjustRelativeBase => {
+                stores = false;
+                writeln!(code, "{}let a = {};", indent, arg(0)).unwrap();
+                writeln!(code, "{}rt.adjust_relative_base(a, {}, {})?;", indent, ip, raw).unwrap();
+            },
+            Opcode::Stop => {
+                writeln!(code, "{}return Ok(rt.halt({}))", indent, ip).unwrap();
+                return code
+            },
+        }
+
+        if span.exit == Exit::Next(next) {
+            writeln!(code, "{}rt.jump({});", indent, next).unwrap();
+            return code
+        }
+        // The rest of this block may just have been written over. Writes to
+        // instructions which have already run don't matter until the next
+        // time the block starts
+        if stores {
+            writeln!(code, "{}if ({}..{}).contains(&address) && rt.invalid({}) {{", indent, next, end, index).unwrap();
+            interpret(&mut code, &format!("{}    ", indent), next);
+            writeln!(code, "{}}}", indent).unwrap();
+        }
+        address = next;
+    }
+}
+
+#[test]
+fn test_transpile() {
+    let source = transpile(&[1002,4,3,4,33]);
+    assert!(source.contains("pub const BLOCKS: &[Block] = &[\n    Block { start: 0, code: &[1002, 4, 3, 4] },\n];"));
+    assert!(source.contains("// 0000: MUL [4], #3, ->4\n"));
+    assert!(source.contains("if let Some(status) = rt.interpret(4)? {"));
+}
+// Generates a Rust module with a run() function which runs the program on a
+// Machine, in place of Machine::run(). The machine should have been created
+// from the same tape, but is free to have input queued, memory patched, or
+// even to have already run for a while
+pub fn transpile(tape: &[Word]) -> String {
+    let mut blocks = String::new();
+    let mut arms = String::new();
+
+    for (index, span) in analysis::blocks(tape).iter().enumerate() {
+        let (start, code) = (span.start, block(tape, span, index));
+        let cells: Vec<String> = (start..span.end)
+            .map(|x| tape.get(x).copied().unwrap_or(0).to_string())
+            .collect();
+        writeln!(blocks, "    Block {{ start: {}, code: &[{}] }},", start, cells.join(", ")).unwrap();
+        writeln!(arms, "            {} => {{", start).unwrap();
+        arms.push_str(&code);
+        writeln!(arms, "            }},").unwrap();
+    }
+
+    // Word is only needed for comparisons
+    let imports = match arms.contains("Word::") {
+        true  => "IntcodeError, Machine, Status, Word",
+        false => "IntcodeError, Machine, Status",
+    };
+    format!(
+        "// Generated by intcode-transpile. Do not edit
+use util::intcode::transpile::{{Block, Runtime}};
+use util::intcode::{{{}}};
+
+pub const BLOCKS: &[Block] = &[
+{}];
+
+pub fn run(machine: &mut Machine) -> Result<Status, IntcodeError> {{
+    let mut rt = Runtime::new(machine, BLOCKS);
+    if rt.halted() {{
+        return Ok(Status::Halted)
+    }}
+    loop {{
+        match rt.ip() {{
+{}            ip => {{
+                if let Some(status) = rt.interpret(ip)? {{
+                    return Ok(status)
+                }}
+            }},
+        }}
+    }}
+}}
+",
+        imports,
+        blocks,
+        arms,
+    )
+}
@@ -5,7 +5,7 @@ pub mod intcode;
 fn test_input_to_str_vec(){
     assert_eq!(
         vec!["first","second","third"],
-        input_to_str_vec(&"first\nsecond\nthird\n")
+        input_to_str_vec("first\nsecond\nthird\n")
     );
 }
 pub fn input_to_str_vec(input: &str) -> Vec<&str> {
@@ -0,0 +1,168 @@
+// Differential tests for intcode-transpile: a transpiled program must leave a
+// machine in exactly the same state as the interpreter would, including when
+// it has to fall back on the interpreter.
+//
+// The modules under transpiled/ are generated from the puzzle inputs and
+// from the small tapes next to them, and need regenerating whenever the
+// transpiler changes:
+//   cargo run --bin intcode-transpile src/bin/day2/input 1=12 2=2 > tests/transpiled/day2.rs
+//   cargo run --bin intcode-transpile src/bin/day5/input 6=1105 > tests/transpiled/day5.rs
+//   cargo run --bin intcode-transpile tests/transpiled/countdown.intcode > tests/transpiled/countdown.rs
+//   cargo run --bin intcode-transpile tests/transpiled/selfmod.intcode > tests/transpiled/selfmod.rs
+// countdown reads n and outputs n down to 1. selfmod reads n, uses relative
+// mode and a computed jump, and writes over the operand of an OUT later in
+// the same block
+#[path = "transpiled/countdown.rs"]
+mod countdown;
+#[path = "transpiled/day2.rs"]
+mod day2;
+#[path = "transpiled/day5.rs"]
+mod day5;
+#[path = "transpiled/selfmod.rs"]
+mod selfmod;
+
+use std::sync::Arc;
+
+use util::intcode::instruction_set::InstructionSet;
+use util::intcode::transpile::transpile;
+use util::intcode::{IntcodeError, Machine, Status, Word};
+
+type Run = fn(&mut Machine) -> Result<Status, IntcodeError>;
+
+const COUNTDOWN: &str = include_str!("transpiled/countdown.intcode");
+const DAY2: &str = include_str!("../src/bin/day2/input");
+const DAY5: &str = include_str!("../src/bin/day5/input");
+const SELFMOD: &str = include_str!("transpiled/selfmod.intcode");
+
+fn patched(input: &str, patches: &[(usize, Word)]) -> Vec<Word> {
+    let mut tape: Vec<Word> = util::input_to_vec_t_fromstr(input, ',');
+    for (address, value) in patches.iter() {
+        tape[*address] = *value;
+    }
+    tape
+}
+
+// Runs the program both ways, checks they agree, and hands back the machine
+// which ran the transpiled code
+fn compare(tape: &[Word], input: &[Word], run: Run) -> Machine {
+    compare_with(tape, input, run, |_| {})
+}
+
+// As compare(), with both machines set up the same way before they run
+fn compare_with<F: Fn(&mut Machine)>(tape: &[Word], input: &[Word], run: Run, setup: F) -> Machine {
+    let mut interpreted = Machine::new(tape);
+    let mut transpiled = Machine::new(tape);
+    setup(&mut interpreted);
+    setup(&mut transpiled);
+    interpreted.extend_input(input.iter().copied());
+    transpiled.extend_input(input.iter().copied());
+
+    assert_eq!(run(&mut transpiled), interpreted.run());
+    assert_eq!(transpiled.output(), interpreted.output());
+    assert_eq!(transpiled.ip(), interpreted.ip());
+    assert_eq!(transpiled.relative_base(), interpreted.relative_base());
+    assert_eq!(transpiled.memory().as_slice(), interpreted.memory().as_slice());
+    assert_eq!(transpiled.memory().far_values(), interpreted.memory().far_values());
+    transpiled
+}
+
+#[test]
+fn test_generated_code_up_to_date() {
+    assert_eq!(transpile(&patched(COUNTDOWN, &[])), include_str!("transpiled/countdown.rs"));
+    assert_eq!(transpile(&patched(DAY2, &[(1, 12), (2, 2)])), include_str!("transpiled/day2.rs"));
+    assert_eq!(transpile(&patched(DAY5, &[(6, 1105)])), include_str!("transpiled/day5.rs"));
+    assert_eq!(transpile(&patched(SELFMOD, &[])), include_str!("transpiled/selfmod.rs"));
+}
+
+#[test]
+fn test_countdown() {
+    // Nothing has been changed, so it runs entirely natively, and natively
+    // run code doesn't record which addresses it touched
+    let machine = compare(&patched(COUNTDOWN, &[]), &[3], countdown::run);
+    assert_eq!(machine.output(), &[3, 2, 1]);
+    assert!(machine.memory().touched().is_empty());
+
+    // Counting down by 3 instead means the loop doesn't match, and the
+    // interpreter takes over
+    let machine = compare(&patched(COUNTDOWN, &[(6, -3)]), &[9], countdown::run);
+    assert_eq!(machine.output(), &[9, 6, 3]);
+    assert!(!machine.memory().touched().is_empty());
+
+    // Stopping for input, then carrying on
+    let mut machine = Machine::new(&patched(COUNTDOWN, &[]));
+    assert_eq!(countdown::run(&mut machine), Ok(Status::WaitingForInput));
+    assert_eq!(machine.ip(), 0);
+    machine.push_input(2);
+    assert_eq!(countdown::run(&mut machine), Ok(Status::Halted));
+    assert_eq!(machine.output(), &[2, 1]);
+    assert_eq!(countdown::run(&mut machine), Ok(Status::Halted));
+}
+
+#[test]
+fn test_day2() {
+    // The code was transpiled for this noun and verb, so runs entirely natively
+    let machine = compare(&patched(DAY2, &[(1, 12), (2, 2)]), &[], day2::run);
+    assert_eq!(machine.status(), Status::Halted);
+    assert_eq!(machine.memory().get(0), 3085697);
+    assert!(machine.memory().touched().is_empty());
+
+    // Any other noun and verb mean the first block doesn't match, and the
+    // interpreter takes over
+    for (noun, verb) in [(0, 0), (12, 3), (65, 33), (98, 99)].iter() {
+        let machine = compare(&patched(DAY2, &[(1, *noun), (2, *verb)]), &[], day2::run);
+        assert!(!machine.memory().touched().is_empty());
+    }
+}
+
+#[test]
+fn test_day5() {
+    let tape = patched(DAY5, &[]);
+    let machine = compare(&tape, &[1], day5::run);
+    assert_eq!(machine.output().last(), Some(&6731945));
+
+    // Part 2 gets past the self-modifying instruction at the start, then runs
+    // natively from there on
+    let machine = compare(&tape, &[5], day5::run);
+    assert_eq!(machine.output(), &[9571668]);
+    assert!(machine.memory().touched().iter().all(|x| *x < 10 || *x == 225));
+}
+
+#[test]
+fn test_selfmod() {
+    let tape = patched(SELFMOD, &[]);
+    for input in [0, 3, 5, 10].iter() {
+        compare(&tape, &[*input], selfmod::run);
+    }
+    let machine = compare(&tape, &[5], selfmod::run);
+    assert_eq!(machine.output(), &[1, 15, 100, 200]);
+}
+
+#[test]
+fn test_errors() {
+    // Runs off into a value which isn't an instruction: the interpreter
+    // reports the error, and leaves the machine where it failed
+    let tape = patched(COUNTDOWN, &[(11, 42)]);
+    let machine = compare(&tape, &[2], countdown::run);
+    assert_eq!(machine.ip(), 11);
+}
+
+#[test]
+fn test_instruction_set() {
+    // Generated code only knows the standard instructions, so a machine
+    // with its own instruction set is left to the interpreter throughout
+    let mut set = InstructionSet::standard();
+    set.register(4, "OUT", 1, None, |cpu, args| {
+        let value = args[0].checked_mul(2).ok_or_else(|| cpu.overflow())?;
+        cpu.output(value);
+        Ok(Status::Output(value))
+    });
+    let set = Arc::new(set);
+    let tape = patched(COUNTDOWN, &[]);
+    let machine = compare_with(&tape, &[3], countdown::run, |x| x.set_instruction_set(set.clone()));
+    assert_eq!(machine.output(), &[6, 4, 2]);
+
+    let set = Arc::new(InstructionSet::standard());
+    let machine = compare_with(&tape, &[3], countdown::run, |x| x.set_instruction_set(set.clone()));
+    assert_eq!(machine.output(), &[3, 2, 1]);
+    assert!(!machine.memory().touched().is_empty());
+}
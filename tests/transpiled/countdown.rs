@@ -0,0 +1,80 @@
+// Generated by intcode-transpile. Do not edit
+use util::intcode::transpile::{Block, Runtime};
+use util::intcode::{IntcodeError, Machine, Status};
+
+pub const BLOCKS: &[Block] = &[
+    Block { start: 0, code: &[3, 12] },
+    Block { start: 2, code: &[4, 12, 1001, 12, -1, 12, 1005, 12, 2] },
+    Block { start: 11, code: &[99] },
+];
+
+pub fn run(machine: &mut Machine) -> Result<Status, IntcodeError> {
+    let mut rt = Runtime::new(machine, BLOCKS);
+    if rt.halted() {
+        return Ok(Status::Halted)
+    }
+    loop {
+        match rt.ip() {
+            0 => {
+                if rt.invalid(0) {
+                    if let Some(status) = rt.interpret(0)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0000: MOV ->12
+                let address = rt.address(12, 0, 3)?;
+                match rt.input() {
+                    Some(x) => rt.store(address, x),
+                    None    => { return Ok(rt.wait(0)) },
+                }
+                rt.jump(2);
+            },
+            2 => {
+                if rt.invalid(1) {
+                    if let Some(status) = rt.interpret(2)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0002: OUT [12]
+                let a = rt.load(12, 2, 4)?;
+                rt.output(a);
+                // 0004: ADD [12], #-1, ->12
+                let a = rt.load(12, 4, 1001)?;
+                let b = -1;
+                let address = rt.address(12, 4, 1001)?;
+                let value = rt.add(a, b, 4, 1001)?;
+                rt.store(address, value);
+                if (8..11).contains(&address) && rt.invalid(1) {
+                    if let Some(status) = rt.interpret(8)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0008: JNZ [12], #2
+                let a = rt.load(12, 8, 1005)?;
+                if a != 0 {
+                    rt.jump(2);
+                    continue
+                }
+                rt.jump(11);
+            },
+            11 => {
+                if rt.invalid(2) {
+                    if let Some(status) = rt.interpret(11)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0011: STOP
+                return Ok(rt.halt(11))
+            },
+            ip => {
+                if let Some(status) = rt.interpret(ip)? {
+                    return Ok(status)
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,345 @@
+// Generated by intcode-transpile. Do not edit
+use util::intcode::transpile::{Block, Runtime};
+use util::intcode::{IntcodeError, Machine, Status};
+
+pub const BLOCKS: &[Block] = &[
+    Block { start: 0, code: &[1, 12, 2, 3, 1, 1, 2, 3, 1, 3, 4, 3, 1, 5, 0, 3, 2, 10, 1, 19, 1, 19, 9, 23, 1, 23, 13, 27, 1, 10, 27, 31, 2, 31, 13, 35, 1, 10, 35, 39, 2, 9, 39, 43, 2, 43, 9, 47, 1, 6, 47, 51, 1, 10, 51, 55, 2, 55, 13, 59, 1, 59, 10, 63, 2, 63, 13, 67, 2, 67, 9, 71, 1, 6, 71, 75, 2, 75, 9, 79, 1, 79, 5, 83, 2, 83, 13, 87, 1, 9, 87, 91, 1, 13, 91, 95, 1, 2, 95, 99, 1, 99, 6, 0, 99] },
+];
+
+pub fn run(machine: &mut Machine) -> Result<Status, IntcodeError> {
+    let mut rt = Runtime::new(machine, BLOCKS);
+    if rt.halted() {
+        return Ok(Status::Halted)
+    }
+    loop {
+        match rt.ip() {
+            0 => {
+                if rt.invalid(0) {
+                    if let Some(status) = rt.interpret(0)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0000: ADD [12], [2], ->3
+                let a = rt.load(12, 0, 1)?;
+                let b = rt.load(2, 0, 1)?;
+                let address = rt.address(3, 0, 1)?;
+                let value = rt.add(a, b, 0, 1)?;
+                rt.store(address, value);
+                if (4..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(4)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0004: ADD [1], [2], ->3
+                let a = rt.load(1, 4, 1)?;
+                let b = rt.load(2, 4, 1)?;
+                let address = rt.address(3, 4, 1)?;
+                let value = rt.add(a, b, 4, 1)?;
+                rt.store(address, value);
+                if (8..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(8)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0008: ADD [3], [4], ->3
+                let a = rt.load(3, 8, 1)?;
+                let b = rt.load(4, 8, 1)?;
+                let address = rt.address(3, 8, 1)?;
+                let value = rt.add(a, b, 8, 1)?;
+                rt.store(address, value);
+                if (12..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(12)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0012: ADD [5], [0], ->3
+                let a = rt.load(5, 12, 1)?;
+                let b = rt.load(0, 12, 1)?;
+                let address = rt.address(3, 12, 1)?;
+                let value = rt.add(a, b, 12, 1)?;
+                rt.store(address, value);
+                if (16..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(16)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0016: MUL [10], [1], ->19
+                let a = rt.load(10, 16, 2)?;
+                let b = rt.load(1, 16, 2)?;
+                let address = rt.address(19, 16, 2)?;
+                let value = rt.mul(a, b, 16, 2)?;
+                rt.store(address, value);
+                if (20..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(20)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0020: ADD [19], [9], ->23
+                let a = rt.load(19, 20, 1)?;
+                let b = rt.load(9, 20, 1)?;
+                let address = rt.address(23, 20, 1)?;
+                let value = rt.add(a, b, 20, 1)?;
+                rt.store(address, value);
+                if (24..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(24)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0024: ADD [23], [13], ->27
+                let a = rt.load(23, 24, 1)?;
+                let b = rt.load(13, 24, 1)?;
+                let address = rt.address(27, 24, 1)?;
+                let value = rt.add(a, b, 24, 1)?;
+                rt.store(address, value);
+                if (28..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(28)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0028: ADD [10], [27], ->31
+                let a = rt.load(10, 28, 1)?;
+                let b = rt.load(27, 28, 1)?;
+                let address = rt.address(31, 28, 1)?;
+                let value = rt.add(a, b, 28, 1)?;
+                rt.store(address, value);
+                if (32..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(32)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0032: MUL [31], [13], ->35
+                let a = rt.load(31, 32, 2)?;
+                let b = rt.load(13, 32, 2)?;
+                let address = rt.address(35, 32, 2)?;
+                let value = rt.mul(a, b, 32, 2)?;
+                rt.store(address, value);
+                if (36..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(36)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0036: ADD [10], [35], ->39
+                let a = rt.load(10, 36, 1)?;
+                let b = rt.load(35, 36, 1)?;
+                let address = rt.address(39, 36, 1)?;
+                let value = rt.add(a, b, 36, 1)?;
+                rt.store(address, value);
+                if (40..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(40)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0040: MUL [9], [39], ->43
+                let a = rt.load(9, 40, 2)?;
+                let b = rt.load(39, 40, 2)?;
+                let address = rt.address(43, 40, 2)?;
+                let value = rt.mul(a, b, 40, 2)?;
+                rt.store(address, value);
+                if (44..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(44)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0044: MUL [43], [9], ->47
+                let a = rt.load(43, 44, 2)?;
+                let b = rt.load(9, 44, 2)?;
+                let address = rt.address(47, 44, 2)?;
+                let value = rt.mul(a, b, 44, 2)?;
+                rt.store(address, value);
+                if (48..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(48)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0048: ADD [6], [47], ->51
+                let a = rt.load(6, 48, 1)?;
+                let b = rt.load(47, 48, 1)?;
+                let address = rt.address(51, 48, 1)?;
+                let value = rt.add(a, b, 48, 1)?;
+                rt.store(address, value);
+                if (52..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(52)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0052: ADD [10], [51], ->55
+                let a = rt.load(10, 52, 1)?;
+                let b = rt.load(51, 52, 1)?;
+                let address = rt.address(55, 52, 1)?;
+                let value = rt.add(a, b, 52, 1)?;
+                rt.store(address, value);
+                if (56..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(56)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0056: MUL [55], [13], ->59
+                let a = rt.load(55, 56, 2)?;
+                let b = rt.load(13, 56, 2)?;
+                let address = rt.address(59, 56, 2)?;
+                let value = rt.mul(a, b, 56, 2)?;
+                rt.store(address, value);
+                if (60..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(60)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0060: ADD [59], [10], ->63
+                let a = rt.load(59, 60, 1)?;
+                let b = rt.load(10, 60, 1)?;
+                let address = rt.address(63, 60, 1)?;
+                let value = rt.add(a, b, 60, 1)?;
+                rt.store(address, value);
+                if (64..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(64)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0064: MUL [63], [13], ->67
+                let a = rt.load(63, 64, 2)?;
+                let b = rt.load(13, 64, 2)?;
+                let address = rt.address(67, 64, 2)?;
+                let value = rt.mul(a, b, 64, 2)?;
+                rt.store(address, value);
+                if (68..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(68)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0068: MUL [67], [9], ->71
+                let a = rt.load(67, 68, 2)?;
+                let b = rt.load(9, 68, 2)?;
+                let address = rt.address(71, 68, 2)?;
+                let value = rt.mul(a, b, 68, 2)?;
+                rt.store(address, value);
+                if (72..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(72)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0072: ADD [6], [71], ->75
+                let a = rt.load(6, 72, 1)?;
+                let b = rt.load(71, 72, 1)?;
+                let address = rt.address(75, 72, 1)?;
+                let value = rt.add(a, b, 72, 1)?;
+                rt.store(address, value);
+                if (76..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(76)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0076: MUL [75], [9], ->79
+                let a = rt.load(75, 76, 2)?;
+                let b = rt.load(9, 76, 2)?;
+                let address = rt.address(79, 76, 2)?;
+                let value = rt.mul(a, b, 76, 2)?;
+                rt.store(address, value);
+                if (80..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(80)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0080: ADD [79], [5], ->83
+                let a = rt.load(79, 80, 1)?;
+                let b = rt.load(5, 80, 1)?;
+                let address = rt.address(83, 80, 1)?;
+                let value = rt.add(a, b, 80, 1)?;
+                rt.store(address, value);
+                if (84..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(84)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0084: MUL [83], [13], ->87
+                let a = rt.load(83, 84, 2)?;
+                let b = rt.load(13, 84, 2)?;
+                let address = rt.address(87, 84, 2)?;
+                let value = rt.mul(a, b, 84, 2)?;
+                rt.store(address, value);
+                if (88..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(88)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0088: ADD [9], [87], ->91
+                let a = rt.load(9, 88, 1)?;
+                let b = rt.load(87, 88, 1)?;
+                let address = rt.address(91, 88, 1)?;
+                let value = rt.add(a, b, 88, 1)?;
+                rt.store(address, value);
+                if (92..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(92)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0092: ADD [13], [91], ->95
+                let a = rt.load(13, 92, 1)?;
+                let b = rt.load(91, 92, 1)?;
+                let address = rt.address(95, 92, 1)?;
+                let value = rt.add(a, b, 92, 1)?;
+                rt.store(address, value);
+                if (96..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(96)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0096: ADD [2], [95], ->99
+                let a = rt.load(2, 96, 1)?;
+                let b = rt.load(95, 96, 1)?;
+                let address = rt.address(99, 96, 1)?;
+                let value = rt.add(a, b, 96, 1)?;
+                rt.store(address, value);
+                if (100..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(100)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0100: ADD [99], [6], ->0
+                let a = rt.load(99, 100, 1)?;
+                let b = rt.load(6, 100, 1)?;
+                let address = rt.address(0, 100, 1)?;
+                let value = rt.add(a, b, 100, 1)?;
+                rt.store(address, value);
+                if (104..105).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(104)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0104: STOP
+                return Ok(rt.halt(104))
+            },
+            ip => {
+                if let Some(status) = rt.interpret(ip)? {
+                    return Ok(status)
+                }
+            },
+        }
+    }
+}
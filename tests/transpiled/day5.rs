@@ -0,0 +1,1799 @@
+// Generated by intcode-transpile. Do not edit
+use util::intcode::transpile::{Block, Runtime};
+use util::intcode::{IntcodeError, Machine, Status, Word};
+
+pub const BLOCKS: &[Block] = &[
+    Block { start: 0, code: &[3, 225, 1, 225, 6, 6, 1105, 1, 238] },
+    Block { start: 9, code: &[] },
+    Block { start: 238, code: &[1105, 0, 99999] },
+    Block { start: 241, code: &[1105, 227, 247] },
+    Block { start: 244, code: &[1105, 1, 99999] },
+    Block { start: 247, code: &[1005, 227, 99999] },
+    Block { start: 250, code: &[1005, 0, 256] },
+    Block { start: 253, code: &[1105, 1, 99999] },
+    Block { start: 256, code: &[1106, 227, 99999] },
+    Block { start: 259, code: &[1106, 0, 265] },
+    Block { start: 262, code: &[1105, 1, 99999] },
+    Block { start: 265, code: &[1006, 0, 99999] },
+    Block { start: 268, code: &[1006, 227, 274] },
+    Block { start: 271, code: &[1105, 1, 99999] },
+    Block { start: 274, code: &[1105, 1, 280] },
+    Block { start: 277, code: &[1105, 1, 99999] },
+    Block { start: 280, code: &[1, 225, 225, 225, 1101, 294, 0, 0, 105, 1, 0] },
+    Block { start: 291, code: &[1105, 1, 99999] },
+    Block { start: 294, code: &[1106, 0, 300] },
+    Block { start: 297, code: &[1105, 1, 99999] },
+    Block { start: 300, code: &[1, 225, 225, 225, 1101, 314, 0, 0, 106, 0, 0] },
+    Block { start: 311, code: &[1105, 1, 99999] },
+    Block { start: 314, code: &[108, 226, 677, 224, 1002, 223, 2, 223, 1006, 224, 329] },
+    Block { start: 325, code: &[1001, 223, 1, 223] },
+    Block { start: 329, code: &[1007, 226, 226, 224, 1002, 223, 2, 223, 1006, 224, 344] },
+    Block { start: 340, code: &[101, 1, 223, 223] },
+    Block { start: 344, code: &[1008, 226, 226, 224, 102, 2, 223, 223, 1006, 224, 359] },
+    Block { start: 355, code: &[1001, 223, 1, 223] },
+    Block { start: 359, code: &[107, 226, 677, 224, 1002, 223, 2, 223, 1005, 224, 374] },
+    Block { start: 370, code: &[101, 1, 223, 223] },
+    Block { start: 374, code: &[1107, 677, 226, 224, 102, 2, 223, 223, 1006, 224, 389] },
+    Block { start: 385, code: &[101, 1, 223, 223] },
+    Block { start: 389, code: &[108, 677, 677, 224, 102, 2, 223, 223, 1006, 224, 404] },
+    Block { start: 400, code: &[1001, 223, 1, 223] },
+    Block { start: 404, code: &[1108, 677, 226, 224, 102, 2, 223, 223, 1005, 224, 419] },
+    Block { start: 415, code: &[101, 1, 223, 223] },
+    Block { start: 419, code: &[1007, 677, 226, 224, 1002, 223, 2, 223, 1006, 224, 434] },
+    Block { start: 430, code: &[101, 1, 223, 223] },
+    Block { start: 434, code: &[1107, 226, 226, 224, 1002, 223, 2, 223, 1006, 224, 449] },
+    Block { start: 445, code: &[101, 1, 223, 223] },
+    Block { start: 449, code: &[8, 677, 226, 224, 102, 2, 223, 223, 1006, 224, 464] },
+    Block { start: 460, code: &[1001, 223, 1, 223] },
+    Block { start: 464, code: &[1107, 226, 677, 224, 102, 2, 223, 223, 1005, 224, 479] },
+    Block { start: 475, code: &[1001, 223, 1, 223] },
+    Block { start: 479, code: &[1007, 677, 677, 224, 102, 2, 223, 223, 1005, 224, 494] },
+    Block { start: 490, code: &[1001, 223, 1, 223] },
+    Block { start: 494, code: &[1108, 677, 677, 224, 102, 2, 223, 223, 1006, 224, 509] },
+    Block { start: 505, code: &[101, 1, 223, 223] },
+    Block { start: 509, code: &[1008, 677, 677, 224, 102, 2, 223, 223, 1005, 224, 524] },
+    Block { start: 520, code: &[1001, 223, 1, 223] },
+    Block { start: 524, code: &[107, 226, 226, 224, 1002, 223, 2, 223, 1005, 224, 539] },
+    Block { start: 535, code: &[101, 1, 223, 223] },
+    Block { start: 539, code: &[7, 226, 226, 224, 102, 2, 223, 223, 1005, 224, 554] },
+    Block { start: 550, code: &[101, 1, 223, 223] },
+    Block { start: 554, code: &[1108, 226, 677, 224, 1002, 223, 2, 223, 1006, 224, 569] },
+    Block { start: 565, code: &[1001, 223, 1, 223] },
+    Block { start: 569, code: &[107, 677, 677, 224, 102, 2, 223, 223, 1005, 224, 584] },
+    Block { start: 580, code: &[101, 1, 223, 223] },
+    Block { start: 584, code: &[7, 677, 226, 224, 1002, 223, 2, 223, 1005, 224, 599] },
+    Block { start: 595, code: &[101, 1, 223, 223] },
+    Block { start: 599, code: &[108, 226, 226, 224, 1002, 223, 2, 223, 1005, 224, 614] },
+    Block { start: 610, code: &[101, 1, 223, 223] },
+    Block { start: 614, code: &[1008, 677, 226, 224, 1002, 223, 2, 223, 1005, 224, 629] },
+    Block { start: 625, code: &[1001, 223, 1, 223] },
+    Block { start: 629, code: &[7, 226, 677, 224, 102, 2, 223, 223, 1005, 224, 644] },
+    Block { start: 640, code: &[101, 1, 223, 223] },
+    Block { start: 644, code: &[8, 677, 677, 224, 102, 2, 223, 223, 1005, 224, 659] },
+    Block { start: 655, code: &[1001, 223, 1, 223] },
+    Block { start: 659, code: &[8, 226, 677, 224, 102, 2, 223, 223, 1006, 224, 674] },
+    Block { start: 670, code: &[1001, 223, 1, 223] },
+    Block { start: 674, code: &[4, 223, 99] },
+];
+
+pub fn run(machine: &mut Machine) -> Result<Status, IntcodeError> {
+    let mut rt = Runtime::new(machine, BLOCKS);
+    if rt.halted() {
+        return Ok(Status::Halted)
+    }
+    loop {
+        match rt.ip() {
+            0 => {
+                if rt.invalid(0) {
+                    if let Some(status) = rt.interpret(0)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0000: MOV ->225
+                let address = rt.address(225, 0, 3)?;
+                match rt.input() {
+                    Some(x) => rt.store(address, x),
+                    None    => { return Ok(rt.wait(0)) },
+                }
+                if (2..9).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(2)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0002: ADD [225], [6], ->6
+                let a = rt.load(225, 2, 1)?;
+                let b = rt.load(6, 2, 1)?;
+                let address = rt.address(6, 2, 1)?;
+                let value = rt.add(a, b, 2, 1)?;
+                rt.store(address, value);
+                if (6..9).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(6)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0006: JNZ #1, #238
+                let a = 1;
+                if a != 0 {
+                    rt.jump(238);
+                    continue
+                }
+                rt.jump(9);
+            },
+            9 => {
+                if let Some(status) = rt.interpret(9)? {
+                    return Ok(status)
+                }
+                continue
+            },
+            238 => {
+                if rt.invalid(2) {
+                    if let Some(status) = rt.interpret(238)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0238: JNZ #0, #99999
+                let a = 0;
+                if a != 0 {
+                    rt.jump(99999);
+                    continue
+                }
+                rt.jump(241);
+            },
+            241 => {
+                if rt.invalid(3) {
+                    if let Some(status) = rt.interpret(241)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0241: JNZ #227, #247
+                let a = 227;
+                if a != 0 {
+                    rt.jump(247);
+                    continue
+                }
+                rt.jump(244);
+            },
+            244 => {
+                if rt.invalid(4) {
+                    if let Some(status) = rt.interpret(244)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0244: JNZ #1, #99999
+                let a = 1;
+                if a != 0 {
+                    rt.jump(99999);
+                    continue
+                }
+                rt.jump(247);
+            },
+            247 => {
+                if rt.invalid(5) {
+                    if let Some(status) = rt.interpret(247)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0247: JNZ [227], #99999
+                let a = rt.load(227, 247, 1005)?;
+                if a != 0 {
+                    rt.jump(99999);
+                    continue
+                }
+                rt.jump(250);
+            },
+            250 => {
+                if rt.invalid(6) {
+                    if let Some(status) = rt.interpret(250)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0250: JNZ [0], #256
+                let a = rt.load(0, 250, 1005)?;
+                if a != 0 {
+                    rt.jump(256);
+                    continue
+                }
+                rt.jump(253);
+            },
+            253 => {
+                if rt.invalid(7) {
+                    if let Some(status) = rt.interpret(253)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0253: JNZ #1, #99999
+                let a = 1;
+                if a != 0 {
+                    rt.jump(99999);
+                    continue
+                }
+                rt.jump(256);
+            },
+            256 => {
+                if rt.invalid(8) {
+                    if let Some(status) = rt.interpret(256)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0256: JZ #227, #99999
+                let a = 227;
+                if a == 0 {
+                    rt.jump(99999);
+                    continue
+                }
+                rt.jump(259);
+            },
+            259 => {
+                if rt.invalid(9) {
+                    if let Some(status) = rt.interpret(259)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0259: JZ #0, #265
+                let a = 0;
+                if a == 0 {
+                    rt.jump(265);
+                    continue
+                }
+                rt.jump(262);
+            },
+            262 => {
+                if rt.invalid(10) {
+                    if let Some(status) = rt.interpret(262)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0262: JNZ #1, #99999
+                let a = 1;
+                if a != 0 {
+                    rt.jump(99999);
+                    continue
+                }
+                rt.jump(265);
+            },
+            265 => {
+                if rt.invalid(11) {
+                    if let Some(status) = rt.interpret(265)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0265: JZ [0], #99999
+                let a = rt.load(0, 265, 1006)?;
+                if a == 0 {
+                    rt.jump(99999);
+                    continue
+                }
+                rt.jump(268);
+            },
+            268 => {
+                if rt.invalid(12) {
+                    if let Some(status) = rt.interpret(268)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0268: JZ [227], #274
+                let a = rt.load(227, 268, 1006)?;
+                if a == 0 {
+                    rt.jump(274);
+                    continue
+                }
+                rt.jump(271);
+            },
+            271 => {
+                if rt.invalid(13) {
+                    if let Some(status) = rt.interpret(271)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0271: JNZ #1, #99999
+                let a = 1;
+                if a != 0 {
+                    rt.jump(99999);
+                    continue
+                }
+                rt.jump(274);
+            },
+            274 => {
+                if rt.invalid(14) {
+                    if let Some(status) = rt.interpret(274)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0274: JNZ #1, #280
+                let a = 1;
+                if a != 0 {
+                    rt.jump(280);
+                    continue
+                }
+                rt.jump(277);
+            },
+            277 => {
+                if rt.invalid(15) {
+                    if let Some(status) = rt.interpret(277)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0277: JNZ #1, #99999
+                let a = 1;
+                if a != 0 {
+                    rt.jump(99999);
+                    continue
+                }
+                rt.jump(280);
+            },
+            280 => {
+                if rt.invalid(16) {
+                    if let Some(status) = rt.interpret(280)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0280: ADD [225], [225], ->225
+                let a = rt.load(225, 280, 1)?;
+                let b = rt.load(225, 280, 1)?;
+                let address = rt.address(225, 280, 1)?;
+                let value = rt.add(a, b, 280, 1)?;
+                rt.store(address, value);
+                if (284..291).contains(&address) && rt.invalid(16) {
+                    if let Some(status) = rt.interpret(284)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0284: ADD #294, #0, ->0
+                let a = 294;
+                let b = 0;
+                let address = rt.address(0, 284, 1101)?;
+                let value = rt.add(a, b, 284, 1101)?;
+                rt.store(address, value);
+                if (288..291).contains(&address) && rt.invalid(16) {
+                    if let Some(status) = rt.interpret(288)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0288: JNZ #1, [0]
+                let a = 1;
+                let b = rt.load(0, 288, 105)?;
+                if a != 0 {
+                    let target = rt.address(b, 288, 105)?;
+                    rt.jump(target);
+                    continue
+                }
+                rt.jump(291);
+            },
+            291 => {
+                if rt.invalid(17) {
+                    if let Some(status) = rt.interpret(291)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0291: JNZ #1, #99999
+                let a = 1;
+                if a != 0 {
+                    rt.jump(99999);
+                    continue
+                }
+                rt.jump(294);
+            },
+            294 => {
+                if rt.invalid(18) {
+                    if let Some(status) = rt.interpret(294)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0294: JZ #0, #300
+                let a = 0;
+                if a == 0 {
+                    rt.jump(300);
+                    continue
+                }
+                rt.jump(297);
+            },
+            297 => {
+                if rt.invalid(19) {
+                    if let Some(status) = rt.interpret(297)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0297: JNZ #1, #99999
+                let a = 1;
+                if a != 0 {
+                    rt.jump(99999);
+                    continue
+                }
+                rt.jump(300);
+            },
+            300 => {
+                if rt.invalid(20) {
+                    if let Some(status) = rt.interpret(300)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0300: ADD [225], [225], ->225
+                let a = rt.load(225, 300, 1)?;
+                let b = rt.load(225, 300, 1)?;
+                let address = rt.address(225, 300, 1)?;
+                let value = rt.add(a, b, 300, 1)?;
+                rt.store(address, value);
+                if (304..311).contains(&address) && rt.invalid(20) {
+                    if let Some(status) = rt.interpret(304)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0304: ADD #314, #0, ->0
+                let a = 314;
+                let b = 0;
+                let address = rt.address(0, 304, 1101)?;
+                let value = rt.add(a, b, 304, 1101)?;
+                rt.store(address, value);
+                if (308..311).contains(&address) && rt.invalid(20) {
+                    if let Some(status) = rt.interpret(308)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0308: JZ #0, [0]
+                let a = 0;
+                let b = rt.load(0, 308, 106)?;
+                if a == 0 {
+                    let target = rt.address(b, 308, 106)?;
+                    rt.jump(target);
+                    continue
+                }
+                rt.jump(311);
+            },
+            311 => {
+                if rt.invalid(21) {
+                    if let Some(status) = rt.interpret(311)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0311: JNZ #1, #99999
+                let a = 1;
+                if a != 0 {
+                    rt.jump(99999);
+                    continue
+                }
+                rt.jump(314);
+            },
+            314 => {
+                if rt.invalid(22) {
+                    if let Some(status) = rt.interpret(314)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0314: EQ #226, [677], ->224
+                let a = 226;
+                let b = rt.load(677, 314, 108)?;
+                let address = rt.address(224, 314, 108)?;
+                let value = Word::from(a == b);
+                rt.store(address, value);
+                if (318..325).contains(&address) && rt.invalid(22) {
+                    if let Some(status) = rt.interpret(318)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0318: MUL [223], #2, ->223
+                let a = rt.load(223, 318, 1002)?;
+                let b = 2;
+                let address = rt.address(223, 318, 1002)?;
+                let value = rt.mul(a, b, 318, 1002)?;
+                rt.store(address, value);
+                if (322..325).contains(&address) && rt.invalid(22) {
+                    if let Some(status) = rt.interpret(322)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0322: JZ [224], #329
+                let a = rt.load(224, 322, 1006)?;
+                if a == 0 {
+                    rt.jump(329);
+                    continue
+                }
+                rt.jump(325);
+            },
+            325 => {
+                if rt.invalid(23) {
+                    if let Some(status) = rt.interpret(325)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0325: ADD [223], #1, ->223
+                let a = rt.load(223, 325, 1001)?;
+                let b = 1;
+                let address = rt.address(223, 325, 1001)?;
+                let value = rt.add(a, b, 325, 1001)?;
+                rt.store(address, value);
+                rt.jump(329);
+            },
+            329 => {
+                if rt.invalid(24) {
+                    if let Some(status) = rt.interpret(329)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0329: LT [226], #226, ->224
+                let a = rt.load(226, 329, 1007)?;
+                let b = 226;
+                let address = rt.address(224, 329, 1007)?;
+                let value = Word::from(a < b);
+                rt.store(address, value);
+                if (333..340).contains(&address) && rt.invalid(24) {
+                    if let Some(status) = rt.interpret(333)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0333: MUL [223], #2, ->223
+                let a = rt.load(223, 333, 1002)?;
+                let b = 2;
+                let address = rt.address(223, 333, 1002)?;
+                let value = rt.mul(a, b, 333, 1002)?;
+                rt.store(address, value);
+                if (337..340).contains(&address) && rt.invalid(24) {
+                    if let Some(status) = rt.interpret(337)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0337: JZ [224], #344
+                let a = rt.load(224, 337, 1006)?;
+                if a == 0 {
+                    rt.jump(344);
+                    continue
+                }
+                rt.jump(340);
+            },
+            340 => {
+                if rt.invalid(25) {
+                    if let Some(status) = rt.interpret(340)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0340: ADD #1, [223], ->223
+                let a = 1;
+                let b = rt.load(223, 340, 101)?;
+                let address = rt.address(223, 340, 101)?;
+                let value = rt.add(a, b, 340, 101)?;
+                rt.store(address, value);
+                rt.jump(344);
+            },
+            344 => {
+                if rt.invalid(26) {
+                    if let Some(status) = rt.interpret(344)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0344: EQ [226], #226, ->224
+                let a = rt.load(226, 344, 1008)?;
+                let b = 226;
+                let address = rt.address(224, 344, 1008)?;
+                let value = Word::from(a == b);
+                rt.store(address, value);
+                if (348..355).contains(&address) && rt.invalid(26) {
+                    if let Some(status) = rt.interpret(348)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0348: MUL #2, [223], ->223
+                let a = 2;
+                let b = rt.load(223, 348, 102)?;
+                let address = rt.address(223, 348, 102)?;
+                let value = rt.mul(a, b, 348, 102)?;
+                rt.store(address, value);
+                if (352..355).contains(&address) && rt.invalid(26) {
+                    if let Some(status) = rt.interpret(352)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0352: JZ [224], #359
+                let a = rt.load(224, 352, 1006)?;
+                if a == 0 {
+                    rt.jump(359);
+                    continue
+                }
+                rt.jump(355);
+            },
+            355 => {
+                if rt.invalid(27) {
+                    if let Some(status) = rt.interpret(355)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0355: ADD [223], #1, ->223
+                let a = rt.load(223, 355, 1001)?;
+                let b = 1;
+                let address = rt.address(223, 355, 1001)?;
+                let value = rt.add(a, b, 355, 1001)?;
+                rt.store(address, value);
+                rt.jump(359);
+            },
+            359 => {
+                if rt.invalid(28) {
+                    if let Some(status) = rt.interpret(359)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0359: LT #226, [677], ->224
+                let a = 226;
+                let b = rt.load(677, 359, 107)?;
+                let address = rt.address(224, 359, 107)?;
+                let value = Word::from(a < b);
+                rt.store(address, value);
+                if (363..370).contains(&address) && rt.invalid(28) {
+                    if let Some(status) = rt.interpret(363)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0363: MUL [223], #2, ->223
+                let a = rt.load(223, 363, 1002)?;
+                let b = 2;
+                let address = rt.address(223, 363, 1002)?;
+                let value = rt.mul(a, b, 363, 1002)?;
+                rt.store(address, value);
+                if (367..370).contains(&address) && rt.invalid(28) {
+                    if let Some(status) = rt.interpret(367)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0367: JNZ [224], #374
+                let a = rt.load(224, 367, 1005)?;
+                if a != 0 {
+                    rt.jump(374);
+                    continue
+                }
+                rt.jump(370);
+            },
+            370 => {
+                if rt.invalid(29) {
+                    if let Some(status) = rt.interpret(370)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0370: ADD #1, [223], ->223
+                let a = 1;
+                let b = rt.load(223, 370, 101)?;
+                let address = rt.address(223, 370, 101)?;
+                let value = rt.add(a, b, 370, 101)?;
+                rt.store(address, value);
+                rt.jump(374);
+            },
+            374 => {
+                if rt.invalid(30) {
+                    if let Some(status) = rt.interpret(374)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0374: LT #677, #226, ->224
+                let a = 677;
+                let b = 226;
+                let address = rt.address(224, 374, 1107)?;
+                let value = Word::from(a < b);
+                rt.store(address, value);
+                if (378..385).contains(&address) && rt.invalid(30) {
+                    if let Some(status) = rt.interpret(378)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0378: MUL #2, [223], ->223
+                let a = 2;
+                let b = rt.load(223, 378, 102)?;
+                let address = rt.address(223, 378, 102)?;
+                let value = rt.mul(a, b, 378, 102)?;
+                rt.store(address, value);
+                if (382..385).contains(&address) && rt.invalid(30) {
+                    if let Some(status) = rt.interpret(382)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0382: JZ [224], #389
+                let a = rt.load(224, 382, 1006)?;
+                if a == 0 {
+                    rt.jump(389);
+                    continue
+                }
+                rt.jump(385);
+            },
+            385 => {
+                if rt.invalid(31) {
+                    if let Some(status) = rt.interpret(385)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0385: ADD #1, [223], ->223
+                let a = 1;
+                let b = rt.load(223, 385, 101)?;
+                let address = rt.address(223, 385, 101)?;
+                let value = rt.add(a, b, 385, 101)?;
+                rt.store(address, value);
+                rt.jump(389);
+            },
+            389 => {
+                if rt.invalid(32) {
+                    if let Some(status) = rt.interpret(389)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0389: EQ #677, [677], ->224
+                let a = 677;
+                let b = rt.load(677, 389, 108)?;
+                let address = rt.address(224, 389, 108)?;
+                let value = Word::from(a == b);
+                rt.store(address, value);
+                if (393..400).contains(&address) && rt.invalid(32) {
+                    if let Some(status) = rt.interpret(393)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0393: MUL #2, [223], ->223
+                let a = 2;
+                let b = rt.load(223, 393, 102)?;
+                let address = rt.address(223, 393, 102)?;
+                let value = rt.mul(a, b, 393, 102)?;
+                rt.store(address, value);
+                if (397..400).contains(&address) && rt.invalid(32) {
+                    if let Some(status) = rt.interpret(397)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0397: JZ [224], #404
+                let a = rt.load(224, 397, 1006)?;
+                if a == 0 {
+                    rt.jump(404);
+                    continue
+                }
+                rt.jump(400);
+            },
+            400 => {
+                if rt.invalid(33) {
+                    if let Some(status) = rt.interpret(400)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0400: ADD [223], #1, ->223
+                let a = rt.load(223, 400, 1001)?;
+                let b = 1;
+                let address = rt.address(223, 400, 1001)?;
+                let value = rt.add(a, b, 400, 1001)?;
+                rt.store(address, value);
+                rt.jump(404);
+            },
+            404 => {
+                if rt.invalid(34) {
+                    if let Some(status) = rt.interpret(404)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0404: EQ #677, #226, ->224
+                let a = 677;
+                let b = 226;
+                let address = rt.address(224, 404, 1108)?;
+                let value = Word::from(a == b);
+                rt.store(address, value);
+                if (408..415).contains(&address) && rt.invalid(34) {
+                    if let Some(status) = rt.interpret(408)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0408: MUL #2, [223], ->223
+                let a = 2;
+                let b = rt.load(223, 408, 102)?;
+                let address = rt.address(223, 408, 102)?;
+                let value = rt.mul(a, b, 408, 102)?;
+                rt.store(address, value);
+                if (412..415).contains(&address) && rt.invalid(34) {
+                    if let Some(status) = rt.interpret(412)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0412: JNZ [224], #419
+                let a = rt.load(224, 412, 1005)?;
+                if a != 0 {
+                    rt.jump(419);
+                    continue
+                }
+                rt.jump(415);
+            },
+            415 => {
+                if rt.invalid(35) {
+                    if let Some(status) = rt.interpret(415)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0415: ADD #1, [223], ->223
+                let a = 1;
+                let b = rt.load(223, 415, 101)?;
+                let address = rt.address(223, 415, 101)?;
+                let value = rt.add(a, b, 415, 101)?;
+                rt.store(address, value);
+                rt.jump(419);
+            },
+            419 => {
+                if rt.invalid(36) {
+                    if let Some(status) = rt.interpret(419)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0419: LT [677], #226, ->224
+                let a = rt.load(677, 419, 1007)?;
+                let b = 226;
+                let address = rt.address(224, 419, 1007)?;
+                let value = Word::from(a < b);
+                rt.store(address, value);
+                if (423..430).contains(&address) && rt.invalid(36) {
+                    if let Some(status) = rt.interpret(423)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0423: MUL [223], #2, ->223
+                let a = rt.load(223, 423, 1002)?;
+                let b = 2;
+                let address = rt.address(223, 423, 1002)?;
+                let value = rt.mul(a, b, 423, 1002)?;
+                rt.store(address, value);
+                if (427..430).contains(&address) && rt.invalid(36) {
+                    if let Some(status) = rt.interpret(427)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0427: JZ [224], #434
+                let a = rt.load(224, 427, 1006)?;
+                if a == 0 {
+                    rt.jump(434);
+                    continue
+                }
+                rt.jump(430);
+            },
+            430 => {
+                if rt.invalid(37) {
+                    if let Some(status) = rt.interpret(430)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0430: ADD #1, [223], ->223
+                let a = 1;
+                let b = rt.load(223, 430, 101)?;
+                let address = rt.address(223, 430, 101)?;
+                let value = rt.add(a, b, 430, 101)?;
+                rt.store(address, value);
+                rt.jump(434);
+            },
+            434 => {
+                if rt.invalid(38) {
+                    if let Some(status) = rt.interpret(434)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0434: LT #226, #226, ->224
+                let a = 226;
+                let b = 226;
+                let address = rt.address(224, 434, 1107)?;
+                let value = Word::from(a < b);
+                rt.store(address, value);
+                if (438..445).contains(&address) && rt.invalid(38) {
+                    if let Some(status) = rt.interpret(438)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0438: MUL [223], #2, ->223
+                let a = rt.load(223, 438, 1002)?;
+                let b = 2;
+                let address = rt.address(223, 438, 1002)?;
+                let value = rt.mul(a, b, 438, 1002)?;
+                rt.store(address, value);
+                if (442..445).contains(&address) && rt.invalid(38) {
+                    if let Some(status) = rt.interpret(442)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0442: JZ [224], #449
+                let a = rt.load(224, 442, 1006)?;
+                if a == 0 {
+                    rt.jump(449);
+                    continue
+                }
+                rt.jump(445);
+            },
+            445 => {
+                if rt.invalid(39) {
+                    if let Some(status) = rt.interpret(445)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0445: ADD #1, [223], ->223
+                let a = 1;
+                let b = rt.load(223, 445, 101)?;
+                let address = rt.address(223, 445, 101)?;
+                let value = rt.add(a, b, 445, 101)?;
+                rt.store(address, value);
+                rt.jump(449);
+            },
+            449 => {
+                if rt.invalid(40) {
+                    if let Some(status) = rt.interpret(449)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0449: EQ [677], [226], ->224
+                let a = rt.load(677, 449, 8)?;
+                let b = rt.load(226, 449, 8)?;
+                let address = rt.address(224, 449, 8)?;
+                let value = Word::from(a == b);
+                rt.store(address, value);
+                if (453..460).contains(&address) && rt.invalid(40) {
+                    if let Some(status) = rt.interpret(453)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0453: MUL #2, [223], ->223
+                let a = 2;
+                let b = rt.load(223, 453, 102)?;
+                let address = rt.address(223, 453, 102)?;
+                let value = rt.mul(a, b, 453, 102)?;
+                rt.store(address, value);
+                if (457..460).contains(&address) && rt.invalid(40) {
+                    if let Some(status) = rt.interpret(457)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0457: JZ [224], #464
+                let a = rt.load(224, 457, 1006)?;
+                if a == 0 {
+                    rt.jump(464);
+                    continue
+                }
+                rt.jump(460);
+            },
+            460 => {
+                if rt.invalid(41) {
+                    if let Some(status) = rt.interpret(460)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0460: ADD [223], #1, ->223
+                let a = rt.load(223, 460, 1001)?;
+                let b = 1;
+                let address = rt.address(223, 460, 1001)?;
+                let value = rt.add(a, b, 460, 1001)?;
+                rt.store(address, value);
+                rt.jump(464);
+            },
+            464 => {
+                if rt.invalid(42) {
+                    if let Some(status) = rt.interpret(464)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0464: LT #226, #677, ->224
+                let a = 226;
+                let b = 677;
+                let address = rt.address(224, 464, 1107)?;
+                let value = Word::from(a < b);
+                rt.store(address, value);
+                if (468..475).contains(&address) && rt.invalid(42) {
+                    if let Some(status) = rt.interpret(468)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0468: MUL #2, [223], ->223
+                let a = 2;
+                let b = rt.load(223, 468, 102)?;
+                let address = rt.address(223, 468, 102)?;
+                let value = rt.mul(a, b, 468, 102)?;
+                rt.store(address, value);
+                if (472..475).contains(&address) && rt.invalid(42) {
+                    if let Some(status) = rt.interpret(472)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0472: JNZ [224], #479
+                let a = rt.load(224, 472, 1005)?;
+                if a != 0 {
+                    rt.jump(479);
+                    continue
+                }
+                rt.jump(475);
+            },
+            475 => {
+                if rt.invalid(43) {
+                    if let Some(status) = rt.interpret(475)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0475: ADD [223], #1, ->223
+                let a = rt.load(223, 475, 1001)?;
+                let b = 1;
+                let address = rt.address(223, 475, 1001)?;
+                let value = rt.add(a, b, 475, 1001)?;
+                rt.store(address, value);
+                rt.jump(479);
+            },
+            479 => {
+                if rt.invalid(44) {
+                    if let Some(status) = rt.interpret(479)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0479: LT [677], #677, ->224
+                let a = rt.load(677, 479, 1007)?;
+                let b = 677;
+                let address = rt.address(224, 479, 1007)?;
+                let value = Word::from(a < b);
+                rt.store(address, value);
+                if (483..490).contains(&address) && rt.invalid(44) {
+                    if let Some(status) = rt.interpret(483)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0483: MUL #2, [223], ->223
+                let a = 2;
+                let b = rt.load(223, 483, 102)?;
+                let address = rt.address(223, 483, 102)?;
+                let value = rt.mul(a, b, 483, 102)?;
+                rt.store(address, value);
+                if (487..490).contains(&address) && rt.invalid(44) {
+                    if let Some(status) = rt.interpret(487)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0487: JNZ [224], #494
+                let a = rt.load(224, 487, 1005)?;
+                if a != 0 {
+                    rt.jump(494);
+                    continue
+                }
+                rt.jump(490);
+            },
+            490 => {
+                if rt.invalid(45) {
+                    if let Some(status) = rt.interpret(490)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0490: ADD [223], #1, ->223
+                let a = rt.load(223, 490, 1001)?;
+                let b = 1;
+                let address = rt.address(223, 490, 1001)?;
+                let value = rt.add(a, b, 490, 1001)?;
+                rt.store(address, value);
+                rt.jump(494);
+            },
+            494 => {
+                if rt.invalid(46) {
+                    if let Some(status) = rt.interpret(494)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0494: EQ #677, #677, ->224
+                let a = 677;
+                let b = 677;
+                let address = rt.address(224, 494, 1108)?;
+                let value = Word::from(a == b);
+                rt.store(address, value);
+                if (498..505).contains(&address) && rt.invalid(46) {
+                    if let Some(status) = rt.interpret(498)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0498: MUL #2, [223], ->223
+                let a = 2;
+                let b = rt.load(223, 498, 102)?;
+                let address = rt.address(223, 498, 102)?;
+                let value = rt.mul(a, b, 498, 102)?;
+                rt.store(address, value);
+                if (502..505).contains(&address) && rt.invalid(46) {
+                    if let Some(status) = rt.interpret(502)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0502: JZ [224], #509
+                let a = rt.load(224, 502, 1006)?;
+                if a == 0 {
+                    rt.jump(509);
+                    continue
+                }
+                rt.jump(505);
+            },
+            505 => {
+                if rt.invalid(47) {
+                    if let Some(status) = rt.interpret(505)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0505: ADD #1, [223], ->223
+                let a = 1;
+                let b = rt.load(223, 505, 101)?;
+                let address = rt.address(223, 505, 101)?;
+                let value = rt.add(a, b, 505, 101)?;
+                rt.store(address, value);
+                rt.jump(509);
+            },
+            509 => {
+                if rt.invalid(48) {
+                    if let Some(status) = rt.interpret(509)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0509: EQ [677], #677, ->224
+                let a = rt.load(677, 509, 1008)?;
+                let b = 677;
+                let address = rt.address(224, 509, 1008)?;
+                let value = Word::from(a == b);
+                rt.store(address, value);
+                if (513..520).contains(&address) && rt.invalid(48) {
+                    if let Some(status) = rt.interpret(513)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0513: MUL #2, [223], ->223
+                let a = 2;
+                let b = rt.load(223, 513, 102)?;
+                let address = rt.address(223, 513, 102)?;
+                let value = rt.mul(a, b, 513, 102)?;
+                rt.store(address, value);
+                if (517..520).contains(&address) && rt.invalid(48) {
+                    if let Some(status) = rt.interpret(517)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0517: JNZ [224], #524
+                let a = rt.load(224, 517, 1005)?;
+                if a != 0 {
+                    rt.jump(524);
+                    continue
+                }
+                rt.jump(520);
+            },
+            520 => {
+                if rt.invalid(49) {
+                    if let Some(status) = rt.interpret(520)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0520: ADD [223], #1, ->223
+                let a = rt.load(223, 520, 1001)?;
+                let b = 1;
+                let address = rt.address(223, 520, 1001)?;
+                let value = rt.add(a, b, 520, 1001)?;
+                rt.store(address, value);
+                rt.jump(524);
+            },
+            524 => {
+                if rt.invalid(50) {
+                    if let Some(status) = rt.interpret(524)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0524: LT #226, [226], ->224
+                let a = 226;
+                let b = rt.load(226, 524, 107)?;
+                let address = rt.address(224, 524, 107)?;
+                let value = Word::from(a < b);
+                rt.store(address, value);
+                if (528..535).contains(&address) && rt.invalid(50) {
+                    if let Some(status) = rt.interpret(528)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0528: MUL [223], #2, ->223
+                let a = rt.load(223, 528, 1002)?;
+                let b = 2;
+                let address = rt.address(223, 528, 1002)?;
+                let value = rt.mul(a, b, 528, 1002)?;
+                rt.store(address, value);
+                if (532..535).contains(&address) && rt.invalid(50) {
+                    if let Some(status) = rt.interpret(532)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0532: JNZ [224], #539
+                let a = rt.load(224, 532, 1005)?;
+                if a != 0 {
+                    rt.jump(539);
+                    continue
+                }
+                rt.jump(535);
+            },
+            535 => {
+                if rt.invalid(51) {
+                    if let Some(status) = rt.interpret(535)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0535: ADD #1, [223], ->223
+                let a = 1;
+                let b = rt.load(223, 535, 101)?;
+                let address = rt.address(223, 535, 101)?;
+                let value = rt.add(a, b, 535, 101)?;
+                rt.store(address, value);
+                rt.jump(539);
+            },
+            539 => {
+                if rt.invalid(52) {
+                    if let Some(status) = rt.interpret(539)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0539: LT [226], [226], ->224
+                let a = rt.load(226, 539, 7)?;
+                let b = rt.load(226, 539, 7)?;
+                let address = rt.address(224, 539, 7)?;
+                let value = Word::from(a < b);
+                rt.store(address, value);
+                if (543..550).contains(&address) && rt.invalid(52) {
+                    if let Some(status) = rt.interpret(543)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0543: MUL #2, [223], ->223
+                let a = 2;
+                let b = rt.load(223, 543, 102)?;
+                let address = rt.address(223, 543, 102)?;
+                let value = rt.mul(a, b, 543, 102)?;
+                rt.store(address, value);
+                if (547..550).contains(&address) && rt.invalid(52) {
+                    if let Some(status) = rt.interpret(547)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0547: JNZ [224], #554
+                let a = rt.load(224, 547, 1005)?;
+                if a != 0 {
+                    rt.jump(554);
+                    continue
+                }
+                rt.jump(550);
+            },
+            550 => {
+                if rt.invalid(53) {
+                    if let Some(status) = rt.interpret(550)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0550: ADD #1, [223], ->223
+                let a = 1;
+                let b = rt.load(223, 550, 101)?;
+                let address = rt.address(223, 550, 101)?;
+                let value = rt.add(a, b, 550, 101)?;
+                rt.store(address, value);
+                rt.jump(554);
+            },
+            554 => {
+                if rt.invalid(54) {
+                    if let Some(status) = rt.interpret(554)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0554: EQ #226, #677, ->224
+                let a = 226;
+                let b = 677;
+                let address = rt.address(224, 554, 1108)?;
+                let value = Word::from(a == b);
+                rt.store(address, value);
+                if (558..565).contains(&address) && rt.invalid(54) {
+                    if let Some(status) = rt.interpret(558)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0558: MUL [223], #2, ->223
+                let a = rt.load(223, 558, 1002)?;
+                let b = 2;
+                let address = rt.address(223, 558, 1002)?;
+                let value = rt.mul(a, b, 558, 1002)?;
+                rt.store(address, value);
+                if (562..565).contains(&address) && rt.invalid(54) {
+                    if let Some(status) = rt.interpret(562)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0562: JZ [224], #569
+                let a = rt.load(224, 562, 1006)?;
+                if a == 0 {
+                    rt.jump(569);
+                    continue
+                }
+                rt.jump(565);
+            },
+            565 => {
+                if rt.invalid(55) {
+                    if let Some(status) = rt.interpret(565)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0565: ADD [223], #1, ->223
+                let a = rt.load(223, 565, 1001)?;
+                let b = 1;
+                let address = rt.address(223, 565, 1001)?;
+                let value = rt.add(a, b, 565, 1001)?;
+                rt.store(address, value);
+                rt.jump(569);
+            },
+            569 => {
+                if rt.invalid(56) {
+                    if let Some(status) = rt.interpret(569)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0569: LT #677, [677], ->224
+                let a = 677;
+                let b = rt.load(677, 569, 107)?;
+                let address = rt.address(224, 569, 107)?;
+                let value = Word::from(a < b);
+                rt.store(address, value);
+                if (573..580).contains(&address) && rt.invalid(56) {
+                    if let Some(status) = rt.interpret(573)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0573: MUL #2, [223], ->223
+                let a = 2;
+                let b = rt.load(223, 573, 102)?;
+                let address = rt.address(223, 573, 102)?;
+                let value = rt.mul(a, b, 573, 102)?;
+                rt.store(address, value);
+                if (577..580).contains(&address) && rt.invalid(56) {
+                    if let Some(status) = rt.interpret(577)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0577: JNZ [224], #584
+                let a = rt.load(224, 577, 1005)?;
+                if a != 0 {
+                    rt.jump(584);
+                    continue
+                }
+                rt.jump(580);
+            },
+            580 => {
+                if rt.invalid(57) {
+                    if let Some(status) = rt.interpret(580)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0580: ADD #1, [223], ->223
+                let a = 1;
+                let b = rt.load(223, 580, 101)?;
+                let address = rt.address(223, 580, 101)?;
+                let value = rt.add(a, b, 580, 101)?;
+                rt.store(address, value);
+                rt.jump(584);
+            },
+            584 => {
+                if rt.invalid(58) {
+                    if let Some(status) = rt.interpret(584)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0584: LT [677], [226], ->224
+                let a = rt.load(677, 584, 7)?;
+                let b = rt.load(226, 584, 7)?;
+                let address = rt.address(224, 584, 7)?;
+                let value = Word::from(a < b);
+                rt.store(address, value);
+                if (588..595).contains(&address) && rt.invalid(58) {
+                    if let Some(status) = rt.interpret(588)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0588: MUL [223], #2, ->223
+                let a = rt.load(223, 588, 1002)?;
+                let b = 2;
+                let address = rt.address(223, 588, 1002)?;
+                let value = rt.mul(a, b, 588, 1002)?;
+                rt.store(address, value);
+                if (592..595).contains(&address) && rt.invalid(58) {
+                    if let Some(status) = rt.interpret(592)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0592: JNZ [224], #599
+                let a = rt.load(224, 592, 1005)?;
+                if a != 0 {
+                    rt.jump(599);
+                    continue
+                }
+                rt.jump(595);
+            },
+            595 => {
+                if rt.invalid(59) {
+                    if let Some(status) = rt.interpret(595)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0595: ADD #1, [223], ->223
+                let a = 1;
+                let b = rt.load(223, 595, 101)?;
+                let address = rt.address(223, 595, 101)?;
+                let value = rt.add(a, b, 595, 101)?;
+                rt.store(address, value);
+                rt.jump(599);
+            },
+            599 => {
+                if rt.invalid(60) {
+                    if let Some(status) = rt.interpret(599)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0599: EQ #226, [226], ->224
+                let a = 226;
+                let b = rt.load(226, 599, 108)?;
+                let address = rt.address(224, 599, 108)?;
+                let value = Word::from(a == b);
+                rt.store(address, value);
+                if (603..610).contains(&address) && rt.invalid(60) {
+                    if let Some(status) = rt.interpret(603)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0603: MUL [223], #2, ->223
+                let a = rt.load(223, 603, 1002)?;
+                let b = 2;
+                let address = rt.address(223, 603, 1002)?;
+                let value = rt.mul(a, b, 603, 1002)?;
+                rt.store(address, value);
+                if (607..610).contains(&address) && rt.invalid(60) {
+                    if let Some(status) = rt.interpret(607)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0607: JNZ [224], #614
+                let a = rt.load(224, 607, 1005)?;
+                if a != 0 {
+                    rt.jump(614);
+                    continue
+                }
+                rt.jump(610);
+            },
+            610 => {
+                if rt.invalid(61) {
+                    if let Some(status) = rt.interpret(610)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0610: ADD #1, [223], ->223
+                let a = 1;
+                let b = rt.load(223, 610, 101)?;
+                let address = rt.address(223, 610, 101)?;
+                let value = rt.add(a, b, 610, 101)?;
+                rt.store(address, value);
+                rt.jump(614);
+            },
+            614 => {
+                if rt.invalid(62) {
+                    if let Some(status) = rt.interpret(614)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0614: EQ [677], #226, ->224
+                let a = rt.load(677, 614, 1008)?;
+                let b = 226;
+                let address = rt.address(224, 614, 1008)?;
+                let value = Word::from(a == b);
+                rt.store(address, value);
+                if (618..625).contains(&address) && rt.invalid(62) {
+                    if let Some(status) = rt.interpret(618)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0618: MUL [223], #2, ->223
+                let a = rt.load(223, 618, 1002)?;
+                let b = 2;
+                let address = rt.address(223, 618, 1002)?;
+                let value = rt.mul(a, b, 618, 1002)?;
+                rt.store(address, value);
+                if (622..625).contains(&address) && rt.invalid(62) {
+                    if let Some(status) = rt.interpret(622)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0622: JNZ [224], #629
+                let a = rt.load(224, 622, 1005)?;
+                if a != 0 {
+                    rt.jump(629);
+                    continue
+                }
+                rt.jump(625);
+            },
+            625 => {
+                if rt.invalid(63) {
+                    if let Some(status) = rt.interpret(625)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0625: ADD [223], #1, ->223
+                let a = rt.load(223, 625, 1001)?;
+                let b = 1;
+                let address = rt.address(223, 625, 1001)?;
+                let value = rt.add(a, b, 625, 1001)?;
+                rt.store(address, value);
+                rt.jump(629);
+            },
+            629 => {
+                if rt.invalid(64) {
+                    if let Some(status) = rt.interpret(629)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0629: LT [226], [677], ->224
+                let a = rt.load(226, 629, 7)?;
+                let b = rt.load(677, 629, 7)?;
+                let address = rt.address(224, 629, 7)?;
+                let value = Word::from(a < b);
+                rt.store(address, value);
+                if (633..640).contains(&address) && rt.invalid(64) {
+                    if let Some(status) = rt.interpret(633)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0633: MUL #2, [223], ->223
+                let a = 2;
+                let b = rt.load(223, 633, 102)?;
+                let address = rt.address(223, 633, 102)?;
+                let value = rt.mul(a, b, 633, 102)?;
+                rt.store(address, value);
+                if (637..640).contains(&address) && rt.invalid(64) {
+                    if let Some(status) = rt.interpret(637)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0637: JNZ [224], #644
+                let a = rt.load(224, 637, 1005)?;
+                if a != 0 {
+                    rt.jump(644);
+                    continue
+                }
+                rt.jump(640);
+            },
+            640 => {
+                if rt.invalid(65) {
+                    if let Some(status) = rt.interpret(640)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0640: ADD #1, [223], ->223
+                let a = 1;
+                let b = rt.load(223, 640, 101)?;
+                let address = rt.address(223, 640, 101)?;
+                let value = rt.add(a, b, 640, 101)?;
+                rt.store(address, value);
+                rt.jump(644);
+            },
+            644 => {
+                if rt.invalid(66) {
+                    if let Some(status) = rt.interpret(644)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0644: EQ [677], [677], ->224
+                let a = rt.load(677, 644, 8)?;
+                let b = rt.load(677, 644, 8)?;
+                let address = rt.address(224, 644, 8)?;
+                let value = Word::from(a == b);
+                rt.store(address, value);
+                if (648..655).contains(&address) && rt.invalid(66) {
+                    if let Some(status) = rt.interpret(648)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0648: MUL #2, [223], ->223
+                let a = 2;
+                let b = rt.load(223, 648, 102)?;
+                let address = rt.address(223, 648, 102)?;
+                let value = rt.mul(a, b, 648, 102)?;
+                rt.store(address, value);
+                if (652..655).contains(&address) && rt.invalid(66) {
+                    if let Some(status) = rt.interpret(652)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0652: JNZ [224], #659
+                let a = rt.load(224, 652, 1005)?;
+                if a != 0 {
+                    rt.jump(659);
+                    continue
+                }
+                rt.jump(655);
+            },
+            655 => {
+                if rt.invalid(67) {
+                    if let Some(status) = rt.interpret(655)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0655: ADD [223], #1, ->223
+                let a = rt.load(223, 655, 1001)?;
+                let b = 1;
+                let address = rt.address(223, 655, 1001)?;
+                let value = rt.add(a, b, 655, 1001)?;
+                rt.store(address, value);
+                rt.jump(659);
+            },
+            659 => {
+                if rt.invalid(68) {
+                    if let Some(status) = rt.interpret(659)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0659: EQ [226], [677], ->224
+                let a = rt.load(226, 659, 8)?;
+                let b = rt.load(677, 659, 8)?;
+                let address = rt.address(224, 659, 8)?;
+                let value = Word::from(a == b);
+                rt.store(address, value);
+                if (663..670).contains(&address) && rt.invalid(68) {
+                    if let Some(status) = rt.interpret(663)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0663: MUL #2, [223], ->223
+                let a = 2;
+                let b = rt.load(223, 663, 102)?;
+                let address = rt.address(223, 663, 102)?;
+                let value = rt.mul(a, b, 663, 102)?;
+                rt.store(address, value);
+                if (667..670).contains(&address) && rt.invalid(68) {
+                    if let Some(status) = rt.interpret(667)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0667: JZ [224], #674
+                let a = rt.load(224, 667, 1006)?;
+                if a == 0 {
+                    rt.jump(674);
+                    continue
+                }
+                rt.jump(670);
+            },
+            670 => {
+                if rt.invalid(69) {
+                    if let Some(status) = rt.interpret(670)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0670: ADD [223], #1, ->223
+                let a = rt.load(223, 670, 1001)?;
+                let b = 1;
+                let address = rt.address(223, 670, 1001)?;
+                let value = rt.add(a, b, 670, 1001)?;
+                rt.store(address, value);
+                rt.jump(674);
+            },
+            674 => {
+                if rt.invalid(70) {
+                    if let Some(status) = rt.interpret(674)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0674: OUT [223]
+                let a = rt.load(223, 674, 4)?;
+                rt.output(a);
+                // 0676: STOP
+                return Ok(rt.halt(676))
+            },
+            ip => {
+                if let Some(status) = rt.interpret(ip)? {
+                    return Ok(status)
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,150 @@
+// Generated by intcode-transpile. Do not edit
+use util::intcode::transpile::{Block, Runtime};
+use util::intcode::{IntcodeError, Machine, Status, Word};
+
+pub const BLOCKS: &[Block] = &[
+    Block { start: 0, code: &[3, 50, 109, 40, 21002, 50, 3, 11, 1007, 51, 20, 52, 1008, 51, 9, 53, 1001, 52, 0, 21, 104, 7, 204, 11, 1002, 53, 2, 60, 1001, 60, 35, 60, 105, 1, 60] },
+    Block { start: 35, code: &[104, 100, 104, 200, 99] },
+];
+
+pub fn run(machine: &mut Machine) -> Result<Status, IntcodeError> {
+    let mut rt = Runtime::new(machine, BLOCKS);
+    if rt.halted() {
+        return Ok(Status::Halted)
+    }
+    loop {
+        match rt.ip() {
+            0 => {
+                if rt.invalid(0) {
+                    if let Some(status) = rt.interpret(0)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0000: MOV ->50
+                let address = rt.address(50, 0, 3)?;
+                match rt.input() {
+                    Some(x) => rt.store(address, x),
+                    None    => { return Ok(rt.wait(0)) },
+                }
+                if (2..35).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(2)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0002: ARB #40
+                let a = 40;
+                rt.adjust_relative_base(a, 2, 109)?;
+                // 0004: MUL [50], #3, ->rb+11
+                let a = rt.load(50, 4, 21002)?;
+                let b = 3;
+                let address = { let relative = rt.relative(11, 4, 21002)?; rt.address(relative, 4, 21002)? };
+                let value = rt.mul(a, b, 4, 21002)?;
+                rt.store(address, value);
+                if (8..35).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(8)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0008: LT [51], #20, ->52
+                let a = rt.load(51, 8, 1007)?;
+                let b = 20;
+                let address = rt.address(52, 8, 1007)?;
+                let value = Word::from(a < b);
+                rt.store(address, value);
+                if (12..35).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(12)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0012: EQ [51], #9, ->53
+                let a = rt.load(51, 12, 1008)?;
+                let b = 9;
+                let address = rt.address(53, 12, 1008)?;
+                let value = Word::from(a == b);
+                rt.store(address, value);
+                if (16..35).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(16)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0016: ADD [52], #0, ->21
+                let a = rt.load(52, 16, 1001)?;
+                let b = 0;
+                let address = rt.address(21, 16, 1001)?;
+                let value = rt.add(a, b, 16, 1001)?;
+                rt.store(address, value);
+                if (20..35).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(20)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0020: OUT #7
+                let a = 7;
+                rt.output(a);
+                // 0022: OUT rb+11
+                let a = { let relative = rt.relative(11, 22, 204)?; rt.load(relative, 22, 204)? };
+                rt.output(a);
+                // 0024: MUL [53], #2, ->60
+                let a = rt.load(53, 24, 1002)?;
+                let b = 2;
+                let address = rt.address(60, 24, 1002)?;
+                let value = rt.mul(a, b, 24, 1002)?;
+                rt.store(address, value);
+                if (28..35).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(28)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0028: ADD [60], #35, ->60
+                let a = rt.load(60, 28, 1001)?;
+                let b = 35;
+                let address = rt.address(60, 28, 1001)?;
+                let value = rt.add(a, b, 28, 1001)?;
+                rt.store(address, value);
+                if (32..35).contains(&address) && rt.invalid(0) {
+                    if let Some(status) = rt.interpret(32)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0032: JNZ #1, [60]
+                let a = 1;
+                let b = rt.load(60, 32, 105)?;
+                if a != 0 {
+                    let target = rt.address(b, 32, 105)?;
+                    rt.jump(target);
+                    continue
+                }
+                rt.jump(35);
+            },
+            35 => {
+                if rt.invalid(1) {
+                    if let Some(status) = rt.interpret(35)? {
+                        return Ok(status)
+                    }
+                    continue
+                }
+                // 0035: OUT #100
+                let a = 100;
+                rt.output(a);
+                // 0037: OUT #200
+                let a = 200;
+                rt.output(a);
+                // 0039: STOP
+                return Ok(rt.halt(39))
+            },
+            ip => {
+                if let Some(status) = rt.interpret(ip)? {
+                    return Ok(status)
+                }
+            },
+        }
+    }
+}